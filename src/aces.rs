@@ -1,6 +1,7 @@
 
-use siege_math::{Vec2, Vec3, Mat3};
-use crate::cie1931::{Cie1931, D60};
+use siege_math::Vec2;
+use crate::cie1931::D60;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
 use super::Chromaticity;
 
 pub const ACES_AP0_CHROMATICITIES: Chromaticity = Chromaticity {
@@ -18,14 +19,6 @@ pub const ACES_AP1_CHROMATICITIES: Chromaticity = Chromaticity {
 };
 
 
-// ACES also deals with these:
-// AP1 color primaries (contained within gamut, but is a wide gamut, bent towards
-//                      display-referred spaces)
-//    Red is (0.713, 0.293)
-//    Green is (0.165, 0.830)
-//    Blue is (0.128, 0.044)
-
-
 /// Academy Color Encoding System, ACES-2065-1 (smpte)
 ///   AP0 color primaries (covers entire CIE 1964 standard-observer spectral locus)
 ///      Red is (0.7347, 0.2653)
@@ -35,65 +28,166 @@ pub const ACES_AP1_CHROMATICITIES: Chromaticity = Chromaticity {
 /// Photometrically linear transfer characteristics
 /// Perfectly white diffuser is (1,1,1) and 18% grey is (0.18, 0.18, 0.18)
 /// Values can go outside of the [0,1] range. Values are scene referred.
-#[derive(Debug, Clone)]
-pub struct Aces {
-    pub v: Vec3<f32>
+/// Conversions to and from `Cie1931<D60>` are provided generically for `Rgb`.
+pub type Aces = Rgb<AcesAp0, Linear>;
+
+/// The ACES AP0 primaries, with the ACES (D60) white point
+#[derive(Debug, Clone, Copy)]
+pub struct AcesAp0;
+
+impl RgbPrimaries for AcesAp0 {
+    type White = D60;
+    const CHROMATICITY: Chromaticity = ACES_AP0_CHROMATICITIES;
+}
+
+/// ACEScg (Academy S-2014-004), the ACES working space for rendering and
+/// compositing
+///   AP1 color primaries (a wide gamut, but inside the spectral locus)
+///      Red is (0.713, 0.293)
+///      Green is (0.165, 0.830)
+///      Blue is (0.128, 0.044)
+///   White point is the ACES white (0.32168, 0.33767)
+/// Photometrically linear transfer characteristics, scene referred.
+pub type AcesCg = Rgb<AcesAp1, Linear>;
+
+/// ACEScc (Academy S-2014-003), a pure logarithmic encoding of AP1 for color
+/// grading
+pub type AcesCc = Rgb<AcesAp1, AcesCcTransfer>;
+
+/// ACEScct (Academy S-2016-001), a logarithmic encoding of AP1 for color
+/// grading, with a linear toe that behaves like film grading tools
+pub type AcesCct = Rgb<AcesAp1, AcesCctTransfer>;
+
+/// The ACES AP1 primaries, with the ACES (D60) white point
+#[derive(Debug, Clone, Copy)]
+pub struct AcesAp1;
+
+impl RgbPrimaries for AcesAp1 {
+    type White = D60;
+    const CHROMATICITY: Chromaticity = ACES_AP1_CHROMATICITIES;
+}
+
+// Largest half float, the limit of decoded values
+const HALF_MAX: f64 = 65504.0;
+
+// The logarithmic segment shared by ACEScc and ACEScct. These are computed in
+// f64, as the scale by 17.52 would otherwise lose precision.
+#[inline]
+fn log_encode(linear: f64) -> f32 {
+    ((linear.log2() + 9.72) / 17.52) as f32
+}
+
+#[inline]
+fn log_decode(encoded: f64) -> f32 {
+    if encoded < (HALF_MAX.log2() + 9.72) / 17.52 {
+        2.0_f64.powf(encoded * 17.52 - 9.72) as f32
+    } else {
+        HALF_MAX as f32
+    }
+}
+
+/// The ACEScc logarithmic encoding
+#[derive(Debug, Clone, Copy)]
+pub struct AcesCcTransfer;
+
+impl TransferFunction for AcesCcTransfer {
+    fn encode(linear: f32) -> f32 {
+        let linear = linear as f64;
+        if linear <= 0.0 {
+            log_encode(2.0_f64.powi(-16))
+        } else if linear < 2.0_f64.powi(-15) {
+            log_encode(2.0_f64.powi(-16) + linear * 0.5)
+        } else {
+            log_encode(linear)
+        }
+    }
+
+    fn decode(encoded: f32) -> f32 {
+        let encoded = encoded as f64;
+        if encoded < (9.72 - 15.0) / 17.52 {
+            ((2.0_f64.powf(encoded * 17.52 - 9.72) - 2.0_f64.powi(-16)) * 2.0) as f32
+        } else {
+            log_decode(encoded)
+        }
+    }
+}
+
+/// The ACEScct logarithmic encoding, with a linear toe
+#[derive(Debug, Clone, Copy)]
+pub struct AcesCctTransfer;
+
+impl AcesCctTransfer {
+    const X_BRK: f64 = 0.0078125;
+    const Y_BRK: f64 = 0.155251141552511;
+    const A: f64 = 10.5402377416545;
+    const B: f64 = 0.0729055341958355;
 }
 
-impl Aces {
-    pub fn new(r: f32, g: f32, b: f32) -> Aces {
-        Aces {
-            v: Vec3::new(r, g, b)
+impl TransferFunction for AcesCctTransfer {
+    fn encode(linear: f32) -> f32 {
+        let linear = linear as f64;
+        if linear <= Self::X_BRK {
+            (Self::A * linear + Self::B) as f32
+        } else {
+            log_encode(linear)
         }
     }
 
-    #[inline]
-    pub fn r(&self) -> f32 {
-        self.v[0]
+    fn decode(encoded: f32) -> f32 {
+        let encoded = encoded as f64;
+        if encoded <= Self::Y_BRK {
+            ((encoded - Self::B) / Self::A) as f32
+        } else {
+            log_decode(encoded)
+        }
     }
-    #[inline]
-    pub fn g(&self) -> f32 {
-        self.v[1]
+}
+
+impl From<Aces> for AcesCg {
+    fn from(input: Aces) -> AcesCg {
+        input.convert()
     }
-    #[inline]
-    pub fn b(&self) -> f32 {
-        self.v[2]
+}
+
+impl From<AcesCg> for Aces {
+    fn from(input: AcesCg) -> Aces {
+        input.convert()
     }
 }
 
-impl From<Cie1931<D60>> for Aces {
-    fn from(input: Cie1931<D60>) -> Aces {
-        let m: Mat3<f32> = Mat3::new(
-            1.0498110175, 0.00000000000, -0.0000974845,
-            -0.4959030231, 1.3733130458, 0.0982400361,
-            0.0000000000, 0.00000000000, 0.9912520182
-        );
-        Aces {
-            v: &m * &input.v
-        }
+impl From<AcesCg> for AcesCc {
+    fn from(input: AcesCg) -> AcesCc {
+        AcesCc::from_linear(input)
+    }
+}
+
+impl From<AcesCc> for AcesCg {
+    fn from(input: AcesCc) -> AcesCg {
+        input.to_linear()
+    }
+}
+
+impl From<AcesCg> for AcesCct {
+    fn from(input: AcesCg) -> AcesCct {
+        AcesCct::from_linear(input)
     }
 }
 
-impl From<Aces> for Cie1931<D60> {
-    fn from(input: Aces) -> Cie1931<D60> {
-        let m: Mat3<f32> = Mat3::new(
-            0.9525523959, 0.0000000000, 0.0000936786,
-            0.3439664498, 0.7281660966, -0.0721325464,
-            0.0000000000, 0.0000000000, 1.0088251844
-        );
-        let v = &m * &input.v;
-        Cie1931::<D60>::new(v.x, v.y, v.z)
+impl From<AcesCct> for AcesCg {
+    fn from(input: AcesCct) -> AcesCg {
+        input.to_linear()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cie1931::Cie1931;
 
     #[test]
     fn aces_to_from() {
         let a = Aces::new(0.123, 1.0, 0.234);
-        let b: Cie1931<D60> = From::from(a.clone());
+        let b: Cie1931<D60> = From::from(a);
         let c: Aces = From::from(b);
 
         assert!(a.v[0] - c.v[0] < 0.000001);
@@ -103,4 +197,76 @@ mod tests {
         assert!(a.v[2] - c.v[2] < 0.000001);
         assert!(c.v[2] - a.v[2] < 0.000001);
     }
+
+    #[test]
+    fn test_matrices() {
+        // From the Academy's TB-2014-004
+        let ap0_to_xyz = [
+            [0.9525523959, 0.0, 0.0000936786],
+            [0.3439664498, 0.7281660966, -0.0721325464],
+            [0.0, 0.0, 1.0088251844],
+        ];
+        let ap1_to_xyz = [
+            [0.6624541811, 0.1340042065, 0.1561876870],
+            [0.2722287168, 0.6740817658, 0.0536895174],
+            [-0.0055746495, 0.0040607335, 1.0103391003],
+        ];
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((AcesAp0::RGB_TO_XYZ[i][j] - ap0_to_xyz[i][j]).abs() < 1e-7);
+                assert!((AcesAp1::RGB_TO_XYZ[i][j] - ap1_to_xyz[i][j]).abs() < 1e-7);
+            }
+        }
+
+        // AP0 to AP1, a column at a time
+        let ap0_to_ap1 = [
+            [1.4514393161, -0.2365107469, -0.2149285693],
+            [-0.0765537734, 1.1762296998, -0.0996759264],
+            [0.0083161484, -0.0060324498, 0.9977163014],
+        ];
+        for (j, unit) in [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)].iter().enumerate() {
+            let cg: AcesCg = From::from(Aces::new(unit.0, unit.1, unit.2));
+            assert!((cg.r() - ap0_to_ap1[0][j] as f32).abs() < 1e-5);
+            assert!((cg.g() - ap0_to_ap1[1][j] as f32).abs() < 1e-5);
+            assert!((cg.b() - ap0_to_ap1[2][j] as f32).abs() < 1e-5);
+        }
+
+        let cg = AcesCg::new(0.3, 0.6, 0.1);
+        let back: AcesCg = From::from(Aces::from(cg));
+        assert!((cg.r() - back.r()).abs() < 1e-6);
+        assert!((cg.g() - back.g()).abs() < 1e-6);
+        assert!((cg.b() - back.b()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_log_encodings() {
+        // Reference values from the Academy's specifications
+        let table = [
+            // linear, ACEScc, ACEScct
+            (0.0, -0.3584475, 0.0729055),
+            (0.0078125, 0.1552511, 0.1552511),
+            (0.18, 0.4135884, 0.4135884),
+            (1.0, 0.5547945, 0.5547945),
+            (222.8609, 1.0, 1.0),
+        ];
+        for &(linear, cc, cct) in table.iter() {
+            assert!((AcesCcTransfer::encode(linear) - cc).abs() < 1e-5);
+            assert!((AcesCctTransfer::encode(linear) - cct).abs() < 1e-5);
+            assert!((AcesCctTransfer::decode(cct) - linear).abs() < linear * 1e-5 + 1e-6);
+            if linear > 0.0 {
+                assert!((AcesCcTransfer::decode(cc) - linear).abs() < linear * 1e-5);
+            }
+        }
+
+        // toe of ACEScc, and clamping to half float
+        let tiny = 1.0e-5;
+        assert!((AcesCcTransfer::decode(AcesCcTransfer::encode(tiny)) - tiny).abs() < 1e-9);
+        assert_eq!(AcesCctTransfer::decode(2.0), 65504.0);
+
+        let cct: AcesCct = From::from(AcesCg::new(0.18, 0.01, 2.0));
+        let back: AcesCg = From::from(cct);
+        assert!((back.r() - 0.18).abs() < 1e-6);
+        assert!((back.g() - 0.01).abs() < 1e-7);
+        assert!((back.b() - 2.0).abs() < 1e-5);
+    }
 }
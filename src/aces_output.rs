@@ -0,0 +1,430 @@
+// ACES output transforms: the reference rendering transform (RRT), which
+// renders scene referred ACES into a wide gamut, high dynamic range output
+// encoding (OCES), followed by an output device transform (ODT) that fits
+// OCES onto a particular display.
+//
+// This follows the ACES 1.0.3 CTL reference implementation. The later
+// single stage HDR output transforms of ACES 1.1, and the ACES 2.0 output
+// transform (a different, appearance model based rendering), are not
+// implemented.
+
+use siege_math::Vec3;
+use crate::adaptation::{AdaptationMethod, adaptation_matrix};
+use crate::aces::{Aces, AcesAp0, AcesAp1};
+use crate::cie1931::{Illuminant, D60, D65};
+use crate::hdr::pq_encode_nits;
+use crate::p3::DisplayP3Primaries;
+use crate::rec2020::Rec2020Primaries;
+use crate::rec709::Bt1886Transfer;
+use crate::rgb::{RgbPrimaries, TransferFunction};
+use crate::srgb::{Srgb, SrgbPrimaries, SrgbTransfer};
+use crate::matrix::{self, M3};
+
+/// An ACES output transform: the display (and its viewing conditions) that
+/// ACES is rendered for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcesOutput {
+    /// sRGB monitor, 100 cd/m², dim surround
+    Srgb,
+    /// Rec.709 (BT.1886, gamma 2.4) monitor, 100 cd/m², dim surround
+    Rec709,
+    /// P3 primaries with a D65 white, gamma 2.6, 48 cd/m² cinema
+    P3D65,
+    /// Rec.2100 PQ (ST 2084) on Rec.2020 primaries, 1000 cd/m²
+    Rec2100Pq,
+    /// Stephen Hill's fit of the RRT and sRGB ODT: much faster, but only
+    /// approximate (and without the glow and red modifiers)
+    SrgbFitted,
+}
+
+impl AcesOutput {
+    /// The peak luminance of the display, in cd/m²
+    pub fn peak_luminance(&self) -> f32 {
+        match *self {
+            AcesOutput::Srgb | AcesOutput::Rec709 | AcesOutput::SrgbFitted => 100.0,
+            AcesOutput::P3D65 => 48.0,
+            AcesOutput::Rec2100Pq => 1000.0,
+        }
+    }
+}
+
+impl Aces {
+    /// The reference rendering transform. The output is OCES, still in AP0
+    /// but display referred, with 18% grey at 4.8 and up to 10000 cd/m².
+    pub fn rrt(&self) -> Aces {
+        let v = rrt([self.r() as f64, self.g() as f64, self.b() as f64]);
+        Aces::new(v[0] as f32, v[1] as f32, v[2] as f32)
+    }
+
+    /// Render for a display, giving its encoded code values (0..1) in the
+    /// display's own primaries and transfer function
+    pub fn render(&self, output: AcesOutput) -> Vec3<f32> {
+        let aces = [self.r() as f64, self.g() as f64, self.b() as f64];
+        let v = match output {
+            AcesOutput::Srgb => {
+                let linear = odt_100nits(rrt(aces));
+                linear.map(|c| SrgbTransfer::encode(c as f32) as f64)
+            },
+            AcesOutput::Rec709 => {
+                odt_100nits(rrt(aces)).map(|c| Bt1886Transfer::encode(c as f32) as f64)
+            },
+            AcesOutput::P3D65 => odt_p3d65(rrt(aces)),
+            AcesOutput::Rec2100Pq => odt_rec2100_pq(rrt(aces)),
+            AcesOutput::SrgbFitted => {
+                let linear = aces_fitted(matrix::mul_vec(&AP0_TO_SRGB, aces));
+                linear.map(|c| SrgbTransfer::encode(c.clamp(0.0, 1.0) as f32) as f64)
+            },
+        };
+        Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32)
+    }
+
+    /// Render for an sRGB monitor, using the full transforms
+    pub fn render_srgb(&self) -> Srgb {
+        let v = self.render(AcesOutput::Srgb);
+        Srgb::new(v.x, v.y, v.z)
+    }
+}
+
+// Smallest normal and largest half float
+const HALF_MIN: f64 = 6.103515625e-5;
+const HALF_MAX: f64 = 65504.0;
+
+const AP0_TO_AP1: M3 = matrix::mul(&<AcesAp1 as RgbPrimaries>::XYZ_TO_RGB,
+                                   &<AcesAp0 as RgbPrimaries>::RGB_TO_XYZ);
+const AP1_TO_AP0: M3 = matrix::invert(&AP0_TO_AP1);
+const AP1_TO_XYZ: M3 = <AcesAp1 as RgbPrimaries>::RGB_TO_XYZ;
+const XYZ_TO_AP1: M3 = <AcesAp1 as RgbPrimaries>::XYZ_TO_RGB;
+
+// Luminance weights of AP1
+const AP1_RGB_TO_Y: [f64; 3] = AP1_TO_XYZ[1];
+
+// From the ACES white to the D65 white of the displays
+const D60_TO_D65: M3 = adaptation_matrix(AdaptationMethod::Bradford,
+                                         D60::WHITE_XYZ, D65::WHITE_XYZ);
+
+const AP1_TO_SRGB: M3 = matrix::mul(&<SrgbPrimaries as RgbPrimaries>::XYZ_TO_RGB,
+                                    &matrix::mul(&D60_TO_D65, &AP1_TO_XYZ));
+const AP1_TO_P3_D65: M3 = matrix::mul(&<DisplayP3Primaries as RgbPrimaries>::XYZ_TO_RGB,
+                                      &matrix::mul(&D60_TO_D65, &AP1_TO_XYZ));
+const AP1_TO_REC2020: M3 = matrix::mul(&<Rec2020Primaries as RgbPrimaries>::XYZ_TO_RGB,
+                                       &matrix::mul(&D60_TO_D65, &AP1_TO_XYZ));
+const AP0_TO_SRGB: M3 = matrix::mul(&AP1_TO_SRGB, &AP0_TO_AP1);
+
+// A piecewise quadratic B-spline tone scale in log10-log10 space, with linear
+// extensions below the minimum and above the maximum point. N is the number
+// of coefficients, two more than the number of knots in each half.
+struct Spline<const N: usize> {
+    coefs_low: [f64; N],
+    coefs_high: [f64; N],
+    // (x, y) at the start, middle and end
+    min: (f64, f64),
+    mid: (f64, f64),
+    max: (f64, f64),
+    slope_low: f64,
+    slope_high: f64,
+}
+
+impl<const N: usize> Spline<N> {
+    fn eval(&self, x: f64) -> f64 {
+        let intervals = (N - 3) as f64;
+        let log_x = x.max(HALF_MIN).log10();
+        let (log_min, log_mid, log_max) = (self.min.0.log10(), self.mid.0.log10(),
+                                           self.max.0.log10());
+        let log_y = if log_x <= log_min {
+            log_x * self.slope_low + (self.min.1.log10() - self.slope_low * log_min)
+        } else if log_x < log_mid {
+            segment(&self.coefs_low, intervals * (log_x - log_min) / (log_mid - log_min))
+        } else if log_x < log_max {
+            segment(&self.coefs_high, intervals * (log_x - log_mid) / (log_max - log_mid))
+        } else {
+            log_x * self.slope_high + (self.max.1.log10() - self.slope_high * log_max)
+        };
+        10.0_f64.powf(log_y)
+    }
+}
+
+// One quadratic B-spline segment, at a fractional knot coordinate
+#[inline]
+fn segment(coefs: &[f64], knot: f64) -> f64 {
+    let j = knot as usize;
+    let t = knot - j as f64;
+    let (c0, c1, c2) = (coefs[j], coefs[j + 1], coefs[j + 2]);
+    t * t * (0.5 * c0 - c1 + 0.5 * c2) + t * (c1 - c0) + 0.5 * (c0 + c1)
+}
+
+// The RRT tone scale, mapping 18% grey to 4.8
+const RRT_SPLINE: Spline<6> = Spline {
+    coefs_low: [-4.0000000000, -4.0000000000, -3.1573765773, -0.4852499958,
+                1.8477324706, 1.8477324706],
+    coefs_high: [-0.7185482425, 2.0810307172, 3.6681241237, 4.0000000000,
+                 4.0000000000, 4.0000000000],
+    min: (0.18 / 32768.0, 0.0001),
+    mid: (0.18, 4.8),
+    max: (0.18 * 262144.0, 10000.0),
+    slope_low: 0.0,
+    slope_high: 0.0,
+};
+
+// The ODT tone scale for a 48 cd/m² peak (also used, rescaled, at 100 cd/m²)
+fn odt_48nits_spline() -> Spline<10> {
+    Spline {
+        coefs_low: [-1.6989700043, -1.6989700043, -1.4779000000, -1.2291000000,
+                    -0.8648000000, -0.4480000000, 0.0051800000, 0.4511080334,
+                    0.9113744414, 0.9113744414],
+        coefs_high: [0.5154386965, 0.8470437783, 1.1358000000, 1.3802000000,
+                     1.5197000000, 1.5985000000, 1.6467000000, 1.6746091357,
+                     1.6878733390, 1.6878733390],
+        min: (RRT_SPLINE.eval(0.18 * 2.0_f64.powf(-6.5)), 0.02),
+        mid: (RRT_SPLINE.eval(0.18), 4.8),
+        max: (RRT_SPLINE.eval(0.18 * 2.0_f64.powf(6.5)), 48.0),
+        slope_low: 0.0,
+        slope_high: 0.04,
+    }
+}
+
+// The ODT tone scale for a 1000 cd/m² peak, mapping 18% grey to 10 cd/m²
+fn odt_1000nits_spline() -> Spline<10> {
+    Spline {
+        coefs_low: [-4.9706219331, -3.0293780669, -2.1262000000, -1.5105000000,
+                    -1.0578000000, -0.4668000000, 0.1193800000, 0.7088134201,
+                    1.2911865799, 1.2911865799],
+        coefs_high: [0.8089132070, 1.1910867930, 1.5683000000, 1.9483000000,
+                     2.3083000000, 2.6384000000, 2.8595000000, 2.9872608805,
+                     3.0127391195, 3.0127391195],
+        min: (RRT_SPLINE.eval(0.18 * 2.0_f64.powi(-12)), 0.0001),
+        mid: (RRT_SPLINE.eval(0.18), 10.0),
+        max: (RRT_SPLINE.eval(0.18 * 2.0_f64.powi(10)), 1000.0),
+        slope_low: 3.0,
+        slope_high: 0.06,
+    }
+}
+
+// RRT parameters
+const RRT_GLOW_GAIN: f64 = 0.05;
+const RRT_GLOW_MID: f64 = 0.08;
+const RRT_RED_SCALE: f64 = 0.82;
+const RRT_RED_PIVOT: f64 = 0.03;
+const RRT_RED_HUE: f64 = 0.0;
+const RRT_RED_WIDTH: f64 = 135.0;
+const RRT_SAT_FACTOR: f64 = 0.96;
+
+// ODT parameters
+const CINEMA_WHITE: f64 = 48.0;
+const CINEMA_BLACK: f64 = 0.02;
+const DIM_SURROUND_GAMMA: f64 = 0.9811;
+const ODT_SAT_FACTOR: f64 = 0.93;
+
+fn rrt(aces: [f64; 3]) -> [f64; 3] {
+    // Glow module: lift dark, saturated colors
+    let saturation = rgb_to_saturation(aces);
+    let s = sigmoid_shaper((saturation - 0.4) / 0.2);
+    let glow = 1.0 + glow_fwd(rgb_to_yc(aces), RRT_GLOW_GAIN * s, RRT_GLOW_MID);
+    let mut aces = aces.map(|c| c * glow);
+
+    // Red modifier: pull bright saturated reds back in
+    let hue = center_hue(rgb_to_hue(aces), RRT_RED_HUE);
+    let weight = cubic_basis_shaper(hue, RRT_RED_WIDTH);
+    aces[0] += weight * saturation * (RRT_RED_PIVOT - aces[0]) * (1.0 - RRT_RED_SCALE);
+
+    // Tone scale each channel in the AP1 rendering space
+    let rgb = matrix::mul_vec(&AP0_TO_AP1, aces.map(|c| c.max(0.0)))
+        .map(|c| c.clamp(0.0, HALF_MAX));
+    let rgb = desaturate(rgb, RRT_SAT_FACTOR).map(|c| RRT_SPLINE.eval(c));
+    matrix::mul_vec(&AP1_TO_AP0, rgb)
+}
+
+// The shared part of the 100 cd/m² dim surround ODTs, to linear display sRGB
+// (Rec.709 primaries) in 0..1
+fn odt_100nits(oces: [f64; 3]) -> [f64; 3] {
+    let spline = odt_48nits_spline();
+    let rgb = matrix::mul_vec(&AP0_TO_AP1, oces)
+        .map(|c| (spline.eval(c) - CINEMA_BLACK) / (CINEMA_WHITE - CINEMA_BLACK));
+    let rgb = desaturate(dark_to_dim_surround(rgb), ODT_SAT_FACTOR);
+    matrix::mul_vec(&AP1_TO_SRGB, rgb).map(|c| c.clamp(0.0, 1.0))
+}
+
+fn odt_p3d65(oces: [f64; 3]) -> [f64; 3] {
+    let spline = odt_48nits_spline();
+    let rgb = matrix::mul_vec(&AP0_TO_AP1, oces)
+        .map(|c| (spline.eval(c) - CINEMA_BLACK) / (CINEMA_WHITE - CINEMA_BLACK));
+    matrix::mul_vec(&AP1_TO_P3_D65, rgb).map(|c| c.clamp(0.0, 1.0).powf(1.0 / 2.6))
+}
+
+fn odt_rec2100_pq(oces: [f64; 3]) -> [f64; 3] {
+    let spline = odt_1000nits_spline();
+    // offset so that the black point reaches code value 0
+    let black = 10.0_f64.powf(-4.4550166483);
+    let rgb = matrix::mul_vec(&AP0_TO_AP1, oces).map(|c| spline.eval(c) - black);
+    matrix::mul_vec(&AP1_TO_REC2020, rgb).map(pq_encode_nits)
+}
+
+// Stephen Hill's fit of the RRT and sRGB ODT, from linear sRGB to linear
+// display sRGB. The input matrix includes the RRT desaturation, the output
+// matrix the ODT desaturation.
+pub(crate) fn aces_fitted(rgb: [f64; 3]) -> [f64; 3] {
+    const INPUT: M3 = [
+        [0.59719, 0.35458, 0.04823],
+        [0.07600, 0.90834, 0.01566],
+        [0.02840, 0.13383, 0.83777],
+    ];
+    const OUTPUT: M3 = [
+        [1.60475, -0.53108, -0.07367],
+        [-0.10208, 1.10813, -0.00605],
+        [-0.00327, -0.07276, 1.07602],
+    ];
+    let v = matrix::mul_vec(&INPUT, rgb).map(|v| {
+        (v * (v + 0.0245786) - 0.000090537) / (v * (0.983729 * v + 0.4329510) + 0.238081)
+    });
+    matrix::mul_vec(&OUTPUT, v)
+}
+
+fn rgb_to_saturation(rgb: [f64; 3]) -> f64 {
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    let min = rgb[0].min(rgb[1]).min(rgb[2]);
+    (max.max(1e-10) - min.max(1e-10)) / max.max(1e-2)
+}
+
+// A luminance-like value weighted towards saturated colors
+fn rgb_to_yc(rgb: [f64; 3]) -> f64 {
+    const RADIUS_WEIGHT: f64 = 1.75;
+    let [r, g, b] = rgb;
+    let chroma = (b * (b - g) + g * (g - r) + r * (r - b)).max(0.0).sqrt();
+    (r + g + b + RADIUS_WEIGHT * chroma) / 3.0
+}
+
+// Hue in degrees 0..360, or 0 for greys
+fn rgb_to_hue(rgb: [f64; 3]) -> f64 {
+    let [r, g, b] = rgb;
+    if r == g && g == b {
+        return 0.0;
+    }
+    let hue = (3.0_f64.sqrt() * (g - b)).atan2(2.0 * r - g - b).to_degrees();
+    if hue < 0.0 { hue + 360.0 } else { hue }
+}
+
+fn center_hue(hue: f64, center: f64) -> f64 {
+    let h = hue - center;
+    if h < -180.0 { h + 360.0 } else if h > 180.0 { h - 360.0 } else { h }
+}
+
+fn sigmoid_shaper(x: f64) -> f64 {
+    let t = (1.0 - (x / 2.0).abs()).max(0.0);
+    (1.0 + x.signum() * (1.0 - t * t)) / 2.0
+}
+
+fn glow_fwd(yc: f64, gain: f64, mid: f64) -> f64 {
+    if yc <= 2.0 / 3.0 * mid {
+        gain
+    } else if yc >= 2.0 * mid {
+        0.0
+    } else {
+        gain * (mid / yc - 0.5)
+    }
+}
+
+// A smooth bump of the given width centered on zero, peaking at 1
+fn cubic_basis_shaper(x: f64, width: f64) -> f64 {
+    if x <= -width / 2.0 || x >= width / 2.0 {
+        return 0.0;
+    }
+    let knot = (x + width / 2.0) * 4.0 / width;
+    let j = knot as usize;
+    let t = knot - j as f64;
+    let y = match j {
+        3 => (-t * t * t + 3.0 * t * t - 3.0 * t + 1.0) / 6.0,
+        2 => (3.0 * t * t * t - 6.0 * t * t + 4.0) / 6.0,
+        1 => (-3.0 * t * t * t + 3.0 * t * t + 3.0 * t + 1.0) / 6.0,
+        _ => t * t * t / 6.0,
+    };
+    y * 1.5
+}
+
+// Mix each channel towards the AP1 luminance
+fn desaturate(rgb: [f64; 3], saturation: f64) -> [f64; 3] {
+    let y = AP1_RGB_TO_Y[0] * rgb[0] + AP1_RGB_TO_Y[1] * rgb[1] + AP1_RGB_TO_Y[2] * rgb[2];
+    rgb.map(|c| saturation * c + (1.0 - saturation) * y)
+}
+
+// Raise luminance to a power, keeping chromaticity, to compensate for a dim
+// (rather than dark) viewing surround
+fn dark_to_dim_surround(rgb: [f64; 3]) -> [f64; 3] {
+    let xyz = matrix::mul_vec(&AP1_TO_XYZ, rgb);
+    let scale = if xyz[1] > 0.0 { xyz[1].powf(DIM_SURROUND_GAMMA) / xyz[1] } else { 0.0 };
+    matrix::mul_vec(&XYZ_TO_AP1, xyz.map(|c| c * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_scales() {
+        // the splines pass through their end points
+        assert!((RRT_SPLINE.eval(0.18) - 4.8).abs() < 0.0001);
+        assert!((RRT_SPLINE.eval(0.18 * 262144.0) - 10000.0).abs() < 0.01);
+
+        let odt = odt_48nits_spline();
+        assert!((odt.eval(4.8) - 4.8).abs() < 0.0001);
+        assert!((odt.eval(odt.max.0) - 48.0).abs() < 0.001);
+        let odt = odt_1000nits_spline();
+        assert!((odt.eval(4.8) - 10.0).abs() < 0.0001);
+        assert!((odt.eval(odt.max.0) - 1000.0).abs() < 0.01);
+
+        // and are monotonic
+        let mut last = 0.0;
+        for i in -60..60 {
+            let y = RRT_SPLINE.eval(0.18 * 2.0_f64.powf(i as f64 / 3.0));
+            assert!(y >= last);
+            last = y;
+        }
+    }
+
+    #[test]
+    fn test_grey() {
+        let grey = Aces::new(0.18, 0.18, 0.18);
+        let oces = grey.rrt();
+        assert!((oces.r() - 4.8).abs() < 0.001);
+        assert!((oces.b() - 4.8).abs() < 0.001);
+
+        let expected = [
+            (AcesOutput::Srgb, 0.35595),
+            (AcesOutput::Rec709, 0.38953),
+            (AcesOutput::P3D65, 0.41187),
+            (AcesOutput::Rec2100Pq, 0.29970),
+        ];
+        for &(output, value) in &expected {
+            let v = grey.render(output);
+            assert!((v.x - value).abs() < 0.0005);
+            assert!((v.y - value).abs() < 0.0005);
+            assert!((v.z - value).abs() < 0.0005);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        // black to black, and bright to the display maximum
+        let black = Aces::new(0.0, 0.0, 0.0).render_srgb();
+        assert!(black.r() < 0.001);
+        let white = Aces::new(100.0, 100.0, 100.0).render(AcesOutput::Srgb);
+        assert!(white.x > 0.99 && white.y > 0.99 && white.z > 0.99);
+
+        // monotonic in exposure (up to where the channels converge)
+        let mut last = 0.0;
+        for i in -20..12 {
+            let e = 0.18 * 2.0_f32.powi(i);
+            let v = Aces::new(e, e * 0.5, e * 0.2).render(AcesOutput::Rec2100Pq);
+            assert!(v.x >= last);
+            last = v.x;
+        }
+
+        // the fit is close to the full transforms for modest colors
+        for &(r, g, b) in &[(0.18, 0.18, 0.18), (0.5, 0.3, 0.2), (0.1, 0.2, 0.4),
+                            (0.05, 0.05, 0.05), (1.0, 1.0, 1.0)] {
+            let full = Aces::new(r, g, b).render(AcesOutput::Srgb);
+            let fit = Aces::new(r, g, b).render(AcesOutput::SrgbFitted);
+            assert!((full.x - fit.x).abs() < 0.03);
+            assert!((full.y - fit.y).abs() < 0.03);
+            assert!((full.z - fit.z).abs() < 0.03);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use siege_math::{Vec3, Mat3};
+use crate::cie1931::*;
+use crate::matrix::{self, M3};
+
+/// The cone response model used for von Kries style chromatic adaptation.
+///
+/// Adaptation transforms XYZ into a cone-like response space, scales each
+/// channel by the ratio of the destination and source whites, and transforms
+/// back:  M⁻¹ · diag(ρ'w/ρw, γ'w/γw, β'w/βw) · M
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdaptationMethod {
+    /// Scaling directly in XYZ (the identity as cone response). Generally poor.
+    XyzScaling,
+    /// von Kries, using the Hunt-Pointer-Estevez cone response normalized to D65
+    VonKries,
+    /// Bradford (Lam 1985). The usual choice, and what ICC profiles use.
+    Bradford,
+    /// CAT02, from CIECAM02
+    Cat02,
+    /// CAT16, from CAM16
+    Cat16,
+}
+
+impl AdaptationMethod {
+    /// The XYZ to cone response matrix for this method
+    pub const fn cone_response(&self) -> [[f64; 3]; 3] {
+        match *self {
+            AdaptationMethod::XyzScaling => [
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ],
+            AdaptationMethod::VonKries => [
+                [0.40024, 0.70760, -0.08081],
+                [-0.22630, 1.16532, 0.04570],
+                [0.0, 0.0, 0.91822],
+            ],
+            AdaptationMethod::Bradford => [
+                [0.8951, 0.2664, -0.1614],
+                [-0.7502, 1.7135, 0.0367],
+                [0.0389, -0.0685, 1.0296],
+            ],
+            AdaptationMethod::Cat02 => [
+                [0.7328, 0.4296, -0.1624],
+                [-0.7036, 1.6975, 0.0061],
+                [0.0030, 0.0136, 0.9834],
+            ],
+            AdaptationMethod::Cat16 => [
+                [0.401288, 0.650173, -0.051461],
+                [-0.250268, 1.204414, 0.045854],
+                [-0.002079, 0.048952, 0.953127],
+            ],
+        }
+    }
+}
+
+/// The matrix that adapts CIE XYZ colors seen under the source white to
+/// corresponding colors under the destination white. Whites are given as XYZ
+/// tristimulus values. This is computed in f64, and can be evaluated at compile
+/// time.
+pub const fn adaptation_matrix(method: AdaptationMethod,
+                               source_white: Vec3<f64>,
+                               destination_white: Vec3<f64>) -> [[f64; 3]; 3]
+{
+    let m: M3 = method.cone_response();
+    let src = matrix::mul_vec(&m, [source_white.x, source_white.y, source_white.z]);
+    let dst = matrix::mul_vec(&m, [destination_white.x, destination_white.y, destination_white.z]);
+    let scale = matrix::diag([dst[0] / src[0], dst[1] / src[1], dst[2] / src[2]]);
+    matrix::mul(&matrix::invert(&m), &matrix::mul(&scale, &m))
+}
+
+impl<I: Illuminant> Cie1931<I> {
+    /// Chromatically adapt to another illuminant using the given method.
+    ///
+    /// Typed `From` conversions between every pair of illuminants are also
+    /// provided, and use the Bradford method.
+    pub fn adapt<J: Illuminant>(&self, method: AdaptationMethod) -> Cie1931<J> {
+        let m: Mat3<f32> = matrix::to_mat3(
+            &adaptation_matrix(method, I::WHITE_XYZ, J::WHITE_XYZ));
+        let v = &m * &self.v;
+        Cie1931::new(v.x, v.y, v.z)
+    }
+}
+
+// Implements From between Cie1931 at every pair of the listed illuminants
+// (in both directions), using Bradford with the matrix computed at compile time.
+macro_rules! impl_adaptation {
+    () => {};
+    ($head:ident $(, $tail:ident)*) => {
+        $(
+            impl From<Cie1931<$head>> for Cie1931<$tail> {
+                fn from(input: Cie1931<$head>) -> Cie1931<$tail> {
+                    const M: [[f64; 3]; 3] = adaptation_matrix(
+                        AdaptationMethod::Bradford, $head::WHITE_XYZ, $tail::WHITE_XYZ);
+                    let v = &matrix::to_mat3(&M) * &input.v;
+                    Cie1931::new(v.x, v.y, v.z)
+                }
+            }
+
+            impl From<Cie1931<$tail>> for Cie1931<$head> {
+                fn from(input: Cie1931<$tail>) -> Cie1931<$head> {
+                    const M: [[f64; 3]; 3] = adaptation_matrix(
+                        AdaptationMethod::Bradford, $tail::WHITE_XYZ, $head::WHITE_XYZ);
+                    let v = &matrix::to_mat3(&M) * &input.v;
+                    Cie1931::new(v.x, v.y, v.z)
+                }
+            }
+        )*
+        impl_adaptation!($($tail),*);
+    };
+}
+
+impl_adaptation!(A, B, C, D50, D55, D60, D65, D75, E,
+                 F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+                 LedB1, LedB2, LedB3, LedB4, LedB5, LedBh1, LedRgb1, LedV1, LedV2, Dci);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srgb::LinearSrgb;
+    use crate::aces::Aces;
+
+    const METHODS: [AdaptationMethod; 5] = [
+        AdaptationMethod::XyzScaling,
+        AdaptationMethod::VonKries,
+        AdaptationMethod::Bradford,
+        AdaptationMethod::Cat02,
+        AdaptationMethod::Cat16,
+    ];
+
+    #[test]
+    fn test_white_to_white() {
+        for method in METHODS.iter() {
+            let m = adaptation_matrix(*method, D65::WHITE_XYZ, D50::WHITE_XYZ);
+            let w = matrix::mul_vec(&m, [D65::WHITE_XYZ.x, D65::WHITE_XYZ.y, D65::WHITE_XYZ.z]);
+            assert!((w[0] - D50::WHITE_XYZ.x).abs() < 1e-12);
+            assert!((w[1] - D50::WHITE_XYZ.y).abs() < 1e-12);
+            assert!((w[2] - D50::WHITE_XYZ.z).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bradford_reference() {
+        // http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html
+        // (Lindbloom's whites differ from ours in the 5th place)
+        let m = adaptation_matrix(AdaptationMethod::Bradford, D65::WHITE_XYZ, D50::WHITE_XYZ);
+        let reference = [
+            [1.0478112, 0.0228866, -0.0501270],
+            [0.0295424, 0.9904844, -0.0170491],
+            [-0.0092345, 0.0150436, 0.7521316],
+        ];
+        for r in 0..3 {
+            for c in 0..3 {
+                assert!((m[r][c] - reference[r][c]).abs() < 0.0005);
+            }
+        }
+    }
+
+    #[test]
+    fn test_typed_matches_adapt() {
+        let a = Cie1931::<D65>::new(0.3, 0.4, 0.5);
+        let b: Cie1931<D60> = From::from(a.clone());
+        let c: Cie1931<D60> = a.adapt(AdaptationMethod::Bradford);
+        assert!((b.x() - c.x()).abs() < 0.000001);
+        assert!((b.y() - c.y()).abs() < 0.000001);
+        assert!((b.z() - c.z()).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_srgb_to_aces() {
+        // sRGB white is ACES white
+        let aces: Aces = LinearSrgb::new(1.0, 1.0, 1.0).convert();
+        assert!((aces.r() - 1.0).abs() < 0.0001);
+        assert!((aces.g() - 1.0).abs() < 0.0001);
+        assert!((aces.b() - 1.0).abs() < 0.0001);
+
+        // and back again
+        let a = LinearSrgb::new(0.18, 0.5, 0.02);
+        let b: Aces = a.convert();
+        let c: LinearSrgb = b.convert();
+        assert!((a.r() - c.r()).abs() < 0.00001);
+        assert!((a.g() - c.g()).abs() < 0.00001);
+        assert!((a.b() - c.b()).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,105 @@
+use siege_math::Vec2;
+use crate::cie1931::D65;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
+use crate::srgb::LinearSrgb;
+use super::Chromaticity;
+
+pub const ADOBE_RGB_CHROMATICITIES: Chromaticity = Chromaticity {
+    red:   Vec2 { x: 0.64, y: 0.33 },
+    green: Vec2 { x: 0.21, y: 0.71 },
+    blue:  Vec2 { x: 0.15, y: 0.06 },
+    white: Vec2 { x: 0.3127, y: 0.3290 }
+};
+
+/// The Adobe RGB (1998) primaries, with the D65 white point
+#[derive(Debug, Clone, Copy)]
+pub struct AdobeRgbPrimaries;
+
+impl RgbPrimaries for AdobeRgbPrimaries {
+    type White = D65;
+    const CHROMATICITY: Chromaticity = ADOBE_RGB_CHROMATICITIES;
+}
+
+/// The Adobe RGB (1998) transfer function, a pure gamma of 563/256 (about
+/// 2.2)
+#[derive(Debug, Clone, Copy)]
+pub struct AdobeRgbTransfer;
+
+const GAMMA: f32 = 563.0 / 256.0;
+
+impl TransferFunction for AdobeRgbTransfer {
+    fn encode(x: f32) -> f32 {
+        x.max(0.0).powf(1.0 / GAMMA)
+    }
+
+    fn decode(x: f32) -> f32 {
+        x.max(0.0).powf(GAMMA)
+    }
+}
+
+/// Adobe RGB (1998), gamma encoded
+///   Red is (0.64, 0.33)
+///   Green is (0.21, 0.71)
+///   Blue is (0.15, 0.06)
+///   White point is CIE Standard D65 (0.3127, 0.3290)
+pub type AdobeRgb = Rgb<AdobeRgbPrimaries, AdobeRgbTransfer>;
+
+/// Adobe RGB (1998) with linear transfer characteristics
+pub type LinearAdobeRgb = Rgb<AdobeRgbPrimaries, Linear>;
+
+impl From<LinearSrgb> for LinearAdobeRgb {
+    fn from(input: LinearSrgb) -> LinearAdobeRgb {
+        input.convert()
+    }
+}
+
+impl From<LinearAdobeRgb> for LinearSrgb {
+    fn from(input: LinearAdobeRgb) -> LinearSrgb {
+        input.convert()
+    }
+}
+
+impl From<AdobeRgb> for LinearAdobeRgb {
+    fn from(input: AdobeRgb) -> LinearAdobeRgb {
+        input.to_linear()
+    }
+}
+
+impl From<LinearAdobeRgb> for AdobeRgb {
+    fn from(input: LinearAdobeRgb) -> AdobeRgb {
+        AdobeRgb::from_linear(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::assert_matrix_eq;
+
+    #[test]
+    fn test_matrix() {
+        // Adobe RGB (1998) Color Image Encoding, section 4.3.4.2
+        assert_matrix_eq(
+            &AdobeRgbPrimaries::RGB_TO_XYZ,
+            &[[0.57667, 0.18556, 0.18823],
+              [0.29734, 0.62736, 0.07529],
+              [0.02703, 0.07069, 0.99134]],
+            0.0001);
+    }
+
+    #[test]
+    fn test_conversions() {
+        // sRGB green is inside Adobe RGB, and white stays white
+        let green: LinearAdobeRgb = From::from(LinearSrgb::new(0.0, 1.0, 0.0));
+        assert!((green.r() - 0.2848).abs() < 0.0005);
+        assert!((green.g() - 1.0).abs() < 0.0005);
+        assert!((green.b() - 0.0412).abs() < 0.0005);
+        let white: LinearAdobeRgb = From::from(LinearSrgb::new(1.0, 1.0, 1.0));
+        assert!((white.b() - 1.0).abs() < 0.0001);
+
+        assert!((AdobeRgbTransfer::decode(0.5) - 0.21775).abs() < 0.00001);
+        let a = AdobeRgb::new(0.2, 0.5, 0.8);
+        let b: AdobeRgb = From::from(LinearAdobeRgb::from(a));
+        assert!((a.r() - b.r()).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,499 @@
+// Color appearance models: CIECAM02 and CAM16.
+//
+// These predict how a color looks (lightness, chroma, hue, colorfulness,
+// saturation and brightness) under given viewing conditions. The cone response
+// matrices are shared with the chromatic adaptation module.
+//
+// CIECAM02: CIE 159:2004, and Fairchild, "Color Appearance Models" (3rd ed.)
+// CAM16: Li et al., "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS"
+//        Color Research & Application 42 (2017)
+
+use std::marker::PhantomData;
+use siege_math::Vec3;
+use crate::cie1931::{Cie1931, Illuminant};
+use crate::lms::{ConeFundamentals, Cat02, Cat16, Hpe};
+use crate::matrix::{self, M3};
+
+/// Which color appearance model to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppearanceModel {
+    Ciecam02,
+    Cam16,
+}
+
+/// The relative luminance of the surround (the field outside the background)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surround {
+    /// Surface colors, e.g. a lit room or outdoors
+    Average,
+    /// e.g. a television in a dim room
+    Dim,
+    /// e.g. a projector in a dark room, or a screen at night
+    Dark,
+}
+
+impl Surround {
+    // (F, c, Nc)
+    fn parameters(&self) -> (f64, f64, f64) {
+        match *self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// The appearance correlates of a color
+#[derive(Debug, Clone, Copy)]
+pub struct Appearance {
+    /// Lightness, J (0 to 100)
+    pub lightness: f32,
+    /// Chroma, C
+    pub chroma: f32,
+    /// Hue angle, h, in degrees from 0 up to 360
+    pub hue: f32,
+    /// Colorfulness, M
+    pub colorfulness: f32,
+    /// Saturation, s
+    pub saturation: f32,
+    /// Brightness, Q
+    pub brightness: f32,
+    /// Hue quadrature, H (0 to 400; 0 is red, 100 yellow, 200 green, 300 blue)
+    pub hue_quadrature: f32,
+}
+
+/// Viewing conditions for a color appearance model, with the adapting white of
+/// illuminant I (or a measured white relative to it).
+#[derive(Debug, Clone)]
+pub struct ViewingConditions<I: Illuminant> {
+    model: AppearanceModel,
+    white: [f64; 3],
+    adapting_luminance: f64,
+    background_luminance: f64,
+    surround: Surround,
+
+    // derived
+    c: f64,
+    nc: f64,
+    n: f64,
+    z: f64,
+    fl: f64,
+    nbb: f64,
+    ncb: f64,
+    d_rgb: [f64; 3],
+    aw: f64,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant> ViewingConditions<I> {
+    /// Viewing conditions with the white point of illuminant I as the adapting
+    /// white.
+    ///
+    /// `adapting_luminance` (L_A) is in cd/m², commonly 20% of the luminance of
+    /// a white object in the scene. `background_luminance` (Y_b) is the relative
+    /// luminance of the background, where white is 100; 20 is typical.
+    pub fn new(model: AppearanceModel, adapting_luminance: f32,
+               background_luminance: f32, surround: Surround) -> ViewingConditions<I>
+    {
+        let w = I::WHITE_XYZ;
+        Self::build(model, [w.x, w.y, w.z], adapting_luminance as f64,
+                    background_luminance as f64, surround, None)
+    }
+
+    /// Viewing conditions with a given adapting white (e.g. a measured display
+    /// white). Its Y is taken as the luminance of white.
+    pub fn with_white(model: AppearanceModel, white: &Cie1931<I>, adapting_luminance: f32,
+                      background_luminance: f32, surround: Surround) -> ViewingConditions<I>
+    {
+        Self::build(model, [white.x() as f64, white.y() as f64, white.z() as f64],
+                    adapting_luminance as f64, background_luminance as f64, surround, None)
+    }
+
+    /// Presume complete adaptation to the white (D=1), as when the observer
+    /// discounts the illuminant, e.g. for surface colors.
+    pub fn discounting_illuminant(self) -> ViewingConditions<I> {
+        Self::build(self.model, self.white, self.adapting_luminance,
+                    self.background_luminance, self.surround, Some(1.0))
+    }
+
+    fn build(model: AppearanceModel, white: [f64; 3], la: f64, yb: f64,
+             surround: Surround, degree: Option<f64>) -> ViewingConditions<I>
+    {
+        let (f, c, nc) = surround.parameters();
+
+        // everything is computed with white at Y=100
+        let scale = 100.0 / white[1];
+        let xyz_w = [white[0] * scale, 100.0, white[2] * scale];
+
+        let k = 1.0 / (5.0 * la + 1.0);
+        let k4 = k * k * k * k;
+        let fl = 0.2 * k4 * (5.0 * la) + 0.1 * (1.0 - k4) * (1.0 - k4) * (5.0 * la).cbrt();
+
+        let n = yb / 100.0;
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 * (1.0 / n).powf(0.2);
+        let ncb = nbb;
+
+        let d = match degree {
+            Some(d) => d,
+            None => (f * (1.0 - (1.0 / 3.6) * ((-la - 42.0) / 92.0).exp())).clamp(0.0, 1.0)
+        };
+
+        let m = cone_matrices(model).0;
+        let rgb_w = matrix::mul_vec(&m, xyz_w);
+        let d_rgb = [
+            d * 100.0 / rgb_w[0] + 1.0 - d,
+            d * 100.0 / rgb_w[1] + 1.0 - d,
+            d * 100.0 / rgb_w[2] + 1.0 - d,
+        ];
+
+        let mut vc = ViewingConditions {
+            model,
+            white,
+            adapting_luminance: la,
+            background_luminance: yb,
+            surround,
+            c,
+            nc,
+            n,
+            z,
+            fl,
+            nbb,
+            ncb,
+            d_rgb,
+            aw: 0.0,
+            _phantom: PhantomData
+        };
+
+        let rgb_a_w = vc.compress(vc.adapt(rgb_w));
+        vc.aw = vc.achromatic(&rgb_a_w);
+        vc
+    }
+
+    /// The luminance level adaptation factor, F_L
+    pub fn luminance_adaptation(&self) -> f32 {
+        self.fl as f32
+    }
+
+    // chromatic adaptation, and (for CIECAM02) conversion to HPE space
+    fn adapt(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let rgb_c = [rgb[0] * self.d_rgb[0], rgb[1] * self.d_rgb[1], rgb[2] * self.d_rgb[2]];
+        match self.model {
+            AppearanceModel::Ciecam02 => {
+                const M: M3 = matrix::mul(&Hpe::XYZ_TO_LMS, &Cat02::LMS_TO_XYZ);
+                matrix::mul_vec(&M, rgb_c)
+            },
+            AppearanceModel::Cam16 => rgb_c,
+        }
+    }
+
+    fn unadapt(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let rgb_c = match self.model {
+            AppearanceModel::Ciecam02 => {
+                const M: M3 = matrix::mul(&Cat02::XYZ_TO_LMS, &Hpe::LMS_TO_XYZ);
+                matrix::mul_vec(&M, rgb)
+            },
+            AppearanceModel::Cam16 => rgb,
+        };
+        [rgb_c[0] / self.d_rgb[0], rgb_c[1] / self.d_rgb[1], rgb_c[2] / self.d_rgb[2]]
+    }
+
+    // post-adaptation non-linear response compression
+    fn compress(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let f = |x: f64| -> f64 {
+            let t = (self.fl * x.abs() / 100.0).powf(0.42);
+            400.0 * x.signum() * t / (t + 27.13) + 0.1
+        };
+        [f(rgb[0]), f(rgb[1]), f(rgb[2])]
+    }
+
+    fn decompress(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let f = |x: f64| -> f64 {
+            let x = x - 0.1;
+            x.signum() * (100.0 / self.fl)
+                * (27.13 * x.abs() / (400.0 - x.abs())).powf(1.0 / 0.42)
+        };
+        [f(rgb[0]), f(rgb[1]), f(rgb[2])]
+    }
+
+    fn achromatic(&self, rgb_a: &[f64; 3]) -> f64 {
+        (2.0 * rgb_a[0] + rgb_a[1] + 0.05 * rgb_a[2] - 0.305) * self.nbb
+    }
+
+    /// Compute the appearance correlates of a color
+    pub fn forward(&self, input: &Cie1931<I>) -> Appearance {
+        let scale = 100.0 / self.white[1];
+        let xyz = [input.x() as f64 * scale, input.y() as f64 * scale, input.z() as f64 * scale];
+
+        let rgb = matrix::mul_vec(&cone_matrices(self.model).0, xyz);
+        let rgb_a = self.compress(self.adapt(rgb));
+
+        let a = rgb_a[0] - 12.0 * rgb_a[1] / 11.0 + rgb_a[2] / 11.0;
+        let b = (rgb_a[0] + rgb_a[1] - 2.0 * rgb_a[2]) / 9.0;
+
+        let mut h = b.atan2(a).to_degrees();
+        if h < 0.0 { h += 360.0; }
+
+        let e_t = 0.25 * ((h.to_radians() + 2.0).cos() + 3.8);
+
+        let achromatic = self.achromatic(&rgb_a);
+        let j = 100.0 * (achromatic / self.aw).max(0.0).powf(self.c * self.z);
+        let q = (4.0 / self.c) * (j / 100.0).sqrt() * (self.aw + 4.0) * self.fl.powf(0.25);
+
+        let t = (50000.0 / 13.0 * self.nc * self.ncb * e_t * a.hypot(b))
+            / (rgb_a[0] + rgb_a[1] + 21.0 / 20.0 * rgb_a[2]);
+        let c = t.powf(0.9) * (j / 100.0).sqrt() * (1.64 - 0.29_f64.powf(self.n)).powf(0.73);
+        let m = c * self.fl.powf(0.25);
+        let s = if q > 0.0 { 100.0 * (m / q).sqrt() } else { 0.0 };
+
+        Appearance {
+            lightness: j as f32,
+            chroma: c as f32,
+            hue: h as f32,
+            colorfulness: m as f32,
+            saturation: s as f32,
+            brightness: q as f32,
+            hue_quadrature: hue_quadrature(h) as f32,
+        }
+    }
+
+    /// Compute the color with the given lightness, chroma and hue. The other
+    /// correlates are ignored.
+    pub fn inverse(&self, input: &Appearance) -> Cie1931<I> {
+        let j = input.lightness as f64;
+        let c = input.chroma as f64;
+        let h = (input.hue as f64).to_radians();
+
+        let t = if j > 0.0 {
+            (c / ((j / 100.0).sqrt() * (1.64 - 0.29_f64.powf(self.n)).powf(0.73)))
+                .powf(1.0 / 0.9)
+        } else {
+            0.0
+        };
+
+        let e_t = 0.25 * ((h + 2.0).cos() + 3.8);
+        let achromatic = self.aw * (j / 100.0).powf(1.0 / (self.c * self.z));
+
+        let p2 = achromatic / self.nbb + 0.305;
+        let p3 = 21.0 / 20.0;
+
+        let (a, b) = if t == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let p1 = (50000.0 / 13.0) * self.nc * self.ncb * e_t / t;
+            let (sin, cos) = h.sin_cos();
+            if sin.abs() >= cos.abs() {
+                let p4 = p1 / sin;
+                let b = p2 * (2.0 + p3) * (460.0 / 1403.0)
+                    / (p4 + (2.0 + p3) * (220.0 / 1403.0) * (cos / sin)
+                       - 27.0 / 1403.0 + p3 * (6300.0 / 1403.0));
+                (b * cos / sin, b)
+            } else {
+                let p5 = p1 / cos;
+                let a = p2 * (2.0 + p3) * (460.0 / 1403.0)
+                    / (p5 + (2.0 + p3) * (220.0 / 1403.0)
+                       - (27.0 / 1403.0 - p3 * (6300.0 / 1403.0)) * (sin / cos));
+                (a, a * sin / cos)
+            }
+        };
+
+        let rgb_a = [
+            (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0,
+            (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0,
+            (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0,
+        ];
+
+        let rgb = self.unadapt(self.decompress(rgb_a));
+        let xyz = matrix::mul_vec(&cone_matrices(self.model).1, rgb);
+
+        let scale = self.white[1] / 100.0;
+        Cie1931::new((xyz[0] * scale) as f32, (xyz[1] * scale) as f32, (xyz[2] * scale) as f32)
+    }
+
+    /// Compute the CAM16-UCS coordinates of a color (CAM02-UCS if the model is
+    /// CIECAM02)
+    pub fn forward_ucs(&self, input: &Cie1931<I>) -> Cam16Ucs {
+        From::from(self.forward(input))
+    }
+
+    /// Compute the color at the given CAM16-UCS coordinates
+    pub fn inverse_ucs(&self, input: &Cam16Ucs) -> Cie1931<I> {
+        let (j, m, h) = input.jmh();
+        let c = m / self.fl.powf(0.25) as f32;
+        self.inverse(&Appearance {
+            lightness: j,
+            chroma: c,
+            hue: h,
+            colorfulness: m,
+            saturation: 0.0,
+            brightness: 0.0,
+            hue_quadrature: 0.0,
+        })
+    }
+}
+
+// the model's XYZ to cone response matrix, and its inverse
+fn cone_matrices(model: AppearanceModel) -> (M3, M3) {
+    match model {
+        AppearanceModel::Ciecam02 => (Cat02::XYZ_TO_LMS, Cat02::LMS_TO_XYZ),
+        AppearanceModel::Cam16 => (Cat16::XYZ_TO_LMS, Cat16::LMS_TO_XYZ),
+    }
+}
+
+fn hue_quadrature(h: f64) -> f64 {
+    // red, yellow, green, blue, red
+    const HUE: [f64; 5] = [20.14, 90.0, 164.25, 237.53, 380.14];
+    const ECCENTRICITY: [f64; 5] = [0.8, 0.7, 1.0, 1.2, 0.8];
+    const QUADRATURE: [f64; 5] = [0.0, 100.0, 200.0, 300.0, 400.0];
+
+    let hp = if h < HUE[0] { h + 360.0 } else { h };
+    let mut i = 0;
+    while i < 3 && hp >= HUE[i + 1] {
+        i += 1;
+    }
+
+    let t1 = (hp - HUE[i]) / ECCENTRICITY[i];
+    let t2 = (HUE[i + 1] - hp) / ECCENTRICITY[i + 1];
+    QUADRATURE[i] + 100.0 * t1 / (t1 + t2)
+}
+
+// CAM16-UCS coefficients (c1, c2), which are also those of CAM02-UCS
+const C1: f32 = 0.007;
+const C2: f32 = 0.0228;
+
+/// CAM16 uniform color space, J′a′b′. Euclidean distance in this space is a
+/// good color difference metric.
+#[derive(Debug, Clone, Copy)]
+pub struct Cam16Ucs {
+    pub v: Vec3<f32>
+}
+
+impl Cam16Ucs {
+    pub fn new(j: f32, a: f32, b: f32) -> Cam16Ucs {
+        Cam16Ucs {
+            v: Vec3::new(j, a, b)
+        }
+    }
+
+    #[inline]
+    pub fn j(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn a(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn b(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Lightness J, colorfulness M and hue angle h (in degrees)
+    pub fn jmh(&self) -> (f32, f32, f32) {
+        let j = self.j() / (1.0 + C1 * (100.0 - self.j()));
+        let mp = self.a().hypot(self.b());
+        let m = ((C2 * mp).exp() - 1.0) / C2;
+        let mut h = self.b().atan2(self.a()).to_degrees();
+        if h < 0.0 { h += 360.0; }
+        (j, m, h)
+    }
+
+    /// Euclidean distance, ΔE′
+    pub fn distance(&self, other: &Cam16Ucs) -> f32 {
+        let dj = self.j() - other.j();
+        let da = self.a() - other.a();
+        let db = self.b() - other.b();
+        (dj * dj + da * da + db * db).sqrt()
+    }
+}
+
+impl From<Appearance> for Cam16Ucs {
+    fn from(input: Appearance) -> Cam16Ucs {
+        let j = (1.0 + 100.0 * C1) * input.lightness / (1.0 + C1 * input.lightness);
+        let m = (1.0 + C2 * input.colorfulness).ln() / C2;
+        let (sin, cos) = input.hue.to_radians().sin_cos();
+        Cam16Ucs::new(j, m * cos, m * sin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::D65;
+
+    fn conditions(model: AppearanceModel) -> ViewingConditions<D65> {
+        let white = Cie1931::<D65>::new(0.9505, 1.0, 1.0888);
+        ViewingConditions::with_white(model, &white, 318.31, 20.0, Surround::Average)
+    }
+
+    #[test]
+    fn test_cam16_reference() {
+        // Reference from colour-science (XYZ_to_CAM16)
+        let vc = conditions(AppearanceModel::Cam16);
+        let a = vc.forward(&Cie1931::new(0.1901, 0.2000, 0.2178));
+        assert!((a.lightness - 41.73121).abs() < 0.001);
+        assert!((a.chroma - 0.1033557).abs() < 0.001);
+        assert!((a.hue - 217.068).abs() < 0.1);
+        assert!((a.saturation - 2.345015).abs() < 0.005);
+        assert!((a.brightness - 195.3717).abs() < 0.005);
+        assert!((a.colorfulness - 0.1074367).abs() < 0.001);
+        assert!((a.hue_quadrature - 275.595).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_ciecam02_reference() {
+        // Reference from colour-science (XYZ_to_CIECAM02), after CIE 159:2004
+        let vc = conditions(AppearanceModel::Ciecam02);
+        let a = vc.forward(&Cie1931::new(0.1901, 0.2000, 0.2178));
+        assert!((a.lightness - 41.73109).abs() < 0.001);
+        assert!((a.chroma - 0.1047077).abs() < 0.001);
+        assert!((a.hue - 219.0484).abs() < 0.1);
+        assert!((a.saturation - 2.3603053).abs() < 0.005);
+        assert!((a.brightness - 195.3713).abs() < 0.005);
+        assert!((a.colorfulness - 0.1088421).abs() < 0.001);
+        assert!((a.hue_quadrature - 278.0607).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_to_and_from() {
+        for model in [AppearanceModel::Ciecam02, AppearanceModel::Cam16].iter() {
+            for surround in [Surround::Average, Surround::Dim, Surround::Dark].iter() {
+                let vc = ViewingConditions::<D65>::new(*model, 64.0, 20.0, *surround);
+                for xyz in &[Cie1931::<D65>::new(0.4124, 0.2126, 0.0193),
+                             Cie1931::<D65>::new(0.1, 0.2, 0.6),
+                             Cie1931::<D65>::new(0.05, 0.05, 0.05)] {
+                    let a = vc.forward(xyz);
+                    let back = vc.inverse(&a);
+                    assert!((xyz.x() - back.x()).abs() < 0.0001);
+                    assert!((xyz.y() - back.y()).abs() < 0.0001);
+                    assert!((xyz.z() - back.z()).abs() < 0.0001);
+
+                    let ucs = vc.forward_ucs(xyz);
+                    let back = vc.inverse_ucs(&ucs);
+                    assert!((xyz.x() - back.x()).abs() < 0.0001);
+                    assert!((xyz.y() - back.y()).abs() < 0.0001);
+                    assert!((xyz.z() - back.z()).abs() < 0.0001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_surround() {
+        // The same color looks lighter in a dark surround
+        let xyz = Cie1931::<D65>::new(0.1, 0.1, 0.1);
+        let average = ViewingConditions::<D65>::new(AppearanceModel::Cam16, 64.0, 20.0,
+                                                    Surround::Average).forward(&xyz);
+        let dark = ViewingConditions::<D65>::new(AppearanceModel::Cam16, 64.0, 20.0,
+                                                 Surround::Dark).forward(&xyz);
+        assert!(dark.lightness > average.lightness);
+
+        // and white is J=100
+        let white = ViewingConditions::<D65>::new(AppearanceModel::Cam16, 64.0, 20.0,
+                                                  Surround::Dark)
+            .discounting_illuminant()
+            .forward(&Cie1931::<D65>::white());
+        assert!((white.lightness - 100.0).abs() < 0.001);
+        assert!(white.chroma < 0.01);
+    }
+}
@@ -1,5 +1,5 @@
-
-use siege_math::Vec2;
+use siege_math::{Vec2, Mat3};
+use crate::matrix::{self, M3};
 
 /// Chromaticity points specified in CIE 1931 XYZ space
 pub struct Chromaticity {
@@ -8,3 +8,110 @@ pub struct Chromaticity {
     pub blue: Vec2<f32>,
     pub white: Vec2<f32>,
 }
+
+// xy to XYZ, at Y=1.0
+const fn xy_to_xyz(xy: &Vec2<f32>) -> [f64; 3] {
+    let x = xy.x as f64;
+    let y = xy.y as f64;
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+impl Chromaticity {
+    /// The normalized primary matrix, which converts linear RGB in this space to
+    /// CIE 1931 XYZ relative to this space's white point (RGB=1,1,1 maps to the
+    /// white point with Y=1.0).  See SMPTE RP 177-1993.
+    ///
+    /// This is computed in f64, and can be evaluated at compile time.
+    pub const fn rgb_to_xyz(&self) -> [[f64; 3]; 3] {
+        let r = xy_to_xyz(&self.red);
+        let g = xy_to_xyz(&self.green);
+        let b = xy_to_xyz(&self.blue);
+        let w = xy_to_xyz(&self.white);
+
+        // Primaries as columns (each at Y=1.0)
+        let p: M3 = [
+            [r[0], g[0], b[0]],
+            [r[1], g[1], b[1]],
+            [r[2], g[2], b[2]],
+        ];
+
+        // Scale each primary so that they sum to the white point
+        let s = matrix::mul_vec(&matrix::invert(&p), w);
+        matrix::mul(&p, &matrix::diag(s))
+    }
+
+    /// The inverse of the normalized primary matrix, converting CIE 1931 XYZ
+    /// (relative to this space's white point) to linear RGB in this space.
+    ///
+    /// This is computed in f64, and can be evaluated at compile time.
+    pub const fn xyz_to_rgb(&self) -> [[f64; 3]; 3] {
+        matrix::invert(&self.rgb_to_xyz())
+    }
+
+    /// `rgb_to_xyz()` as a `Mat3<f32>`
+    pub fn rgb_to_xyz_matrix(&self) -> Mat3<f32> {
+        matrix::to_mat3(&self.rgb_to_xyz())
+    }
+
+    /// `xyz_to_rgb()` as a `Mat3<f32>`
+    pub fn xyz_to_rgb_matrix(&self) -> Mat3<f32> {
+        matrix::to_mat3(&self.xyz_to_rgb())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::srgb::SRGB_CHROMATICITIES;
+    use crate::aces::ACES_AP0_CHROMATICITIES;
+    use crate::matrix::assert_matrix_eq;
+
+    #[test]
+    fn test_srgb_matrices() {
+        // Using the white point exactly as given in IEC 61966-2-1
+        // (Lindbloom's tables use 0.31271, 0.32902 and differ in the 4th place)
+        assert_matrix_eq(
+            &SRGB_CHROMATICITIES.rgb_to_xyz(),
+            &[[0.41239080, 0.35758434, 0.18048079],
+              [0.21263901, 0.71516868, 0.07219232],
+              [0.01933082, 0.11919478, 0.95053215]],
+            0.000001);
+
+        assert_matrix_eq(
+            &SRGB_CHROMATICITIES.xyz_to_rgb(),
+            &[[3.24096994, -1.53738318, -0.49861076],
+              [-0.96924364, 1.87596750, 0.04155506],
+              [0.05563008, -0.20397696, 1.05697151]],
+            0.000001);
+    }
+
+    #[test]
+    fn test_aces_ap0_matrices() {
+        // SMPTE ST 2065-1:2012
+        assert_matrix_eq(
+            &ACES_AP0_CHROMATICITIES.rgb_to_xyz(),
+            &[[0.9525523959, 0.0000000000, 0.0000936786],
+              [0.3439664498, 0.7281660966, -0.0721325464],
+              [0.0000000000, 0.0000000000, 1.0088251844]],
+            0.0000001);
+
+        assert_matrix_eq(
+            &ACES_AP0_CHROMATICITIES.xyz_to_rgb(),
+            &[[1.0498110175, 0.0000000000, -0.0000974845],
+              [-0.4959030231, 1.3733130458, 0.0982400361],
+              [0.0000000000, 0.0000000000, 0.9912520182]],
+            0.0000001);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let m = SRGB_CHROMATICITIES.rgb_to_xyz();
+        let i = SRGB_CHROMATICITIES.xyz_to_rgb();
+        let identity = crate::matrix::mul(&m, &i);
+        assert_matrix_eq(
+            &identity,
+            &[[1.0, 0.0, 0.0],
+              [0.0, 1.0, 0.0],
+              [0.0, 0.0, 1.0]],
+            1e-12);
+    }
+}
@@ -1,20 +1,156 @@
-
 use std::marker::PhantomData;
-use siege_math::{Vec3, Mat3};
+use siege_math::{Vec2, Vec3};
+
+/// A standard illuminant, used as the reference white of a colorspace.
+///
+/// White points are normalized to Y=1.0, like the rest of the crate.
+pub trait Illuminant {
+    /// Chromaticity of the white point (CIE 1931 2° observer)
+    const WHITE_XY: Vec2<f64>;
+
+    /// Tristimulus values of the white point (CIE 1931 2° observer)
+    const WHITE_XYZ: Vec3<f64> = xy_to_xyz(Self::WHITE_XY);
+}
 
-#[derive(Debug, Clone, Copy)]
-pub struct D50;
-#[derive(Debug, Clone, Copy)]
-pub struct D60;
-#[derive(Debug, Clone, Copy)]
-pub struct D65;
-pub trait Illuminant { }
-impl Illuminant for D50 { }
-impl Illuminant for D60 { }
-impl Illuminant for D65 { }
+/// An illuminant for which the CIE 1964 10° observer white point is also
+/// known. All but the LED series (which the CIE tabulates only for 2°) are.
+pub trait Illuminant10: Illuminant {
+    /// Chromaticity of the white point (CIE 1964 10° observer)
+    const WHITE_XY_10: Vec2<f64>;
 
+    /// Tristimulus values of the white point (CIE 1964 10° observer)
+    const WHITE_XYZ_10: Vec3<f64> = xy_to_xyz(Self::WHITE_XY_10);
+}
 
-/// CIE 1931 XYZ colorspace at the D65 whitepoint
+// xy to XYZ, at Y=1.0
+pub(crate) const fn xy_to_xyz(xy: Vec2<f64>) -> Vec3<f64> {
+    Vec3 {
+        x: xy.x / xy.y,
+        y: 1.0,
+        z: (1.0 - xy.x - xy.y) / xy.y
+    }
+}
+
+macro_rules! illuminant {
+    ($(#[$attr:meta])* $name:ident, ($x:expr, $y:expr)) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name;
+        impl Illuminant for $name {
+            const WHITE_XY: Vec2<f64> = Vec2 { x: $x, y: $y };
+        }
+    };
+    ($(#[$attr:meta])* $name:ident, ($x:expr, $y:expr), ($x10:expr, $y10:expr)) => {
+        illuminant!($(#[$attr])* $name, ($x, $y));
+        impl Illuminant10 for $name {
+            const WHITE_XY_10: Vec2<f64> = Vec2 { x: $x10, y: $y10 };
+        }
+    };
+}
+
+// White points are from CIE 15:2004 (D series to 4 places, as tabulated there)
+// and CIE 15:2018 (LED series, 2° only). The 10° white of D60 is computed from
+// the CIE daylight spectrum at its chromaticity.
+
+illuminant!(
+    /// CIE Standard Illuminant A (incandescent tungsten, 2856 K)
+    A, (0.44758, 0.40745), (0.45117, 0.40594));
+illuminant!(
+    /// CIE Illuminant B (direct noon sunlight, 4874 K). Deprecated by the CIE.
+    B, (0.34842, 0.35161), (0.34980, 0.35270));
+illuminant!(
+    /// CIE Illuminant C (average daylight, 6774 K). Deprecated by the CIE.
+    C, (0.31006, 0.31616), (0.31039, 0.31905));
+illuminant!(
+    /// CIE Standard Illuminant D50 (horizon light, ICC profile connection space)
+    D50, (0.3457, 0.3585), (0.34773, 0.35952));
+illuminant!(
+    /// CIE Illuminant D55 (mid-morning / mid-afternoon daylight)
+    D55, (0.3324, 0.3474), (0.33411, 0.34877));
+illuminant!(
+    /// The ACES white point, which is approximately CIE D60
+    D60, (0.32168, 0.33767), (0.3230, 0.3392));
+illuminant!(
+    /// CIE Standard Illuminant D65 (noon daylight, sRGB and Rec.2020)
+    D65, (0.3127, 0.3290), (0.31382, 0.33100));
+illuminant!(
+    /// CIE Illuminant D75 (north sky daylight)
+    D75, (0.2990, 0.3149), (0.29968, 0.31740));
+illuminant!(
+    /// CIE Standard Illuminant E (equal energy)
+    E, (1.0/3.0, 1.0/3.0), (1.0/3.0, 1.0/3.0));
+
+illuminant!(
+    /// CIE Illuminant F1 (daylight fluorescent, 6430 K)
+    F1, (0.31310, 0.33727), (0.31811, 0.33559));
+illuminant!(
+    /// CIE Illuminant F2 (cool white fluorescent, 4230 K)
+    F2, (0.37208, 0.37529), (0.37925, 0.36733));
+illuminant!(
+    /// CIE Illuminant F3 (white fluorescent, 3450 K)
+    F3, (0.40910, 0.39430), (0.41761, 0.38324));
+illuminant!(
+    /// CIE Illuminant F4 (warm white fluorescent, 2940 K)
+    F4, (0.44018, 0.40329), (0.44920, 0.39074));
+illuminant!(
+    /// CIE Illuminant F5 (daylight fluorescent, 6350 K)
+    F5, (0.31379, 0.34531), (0.31975, 0.34246));
+illuminant!(
+    /// CIE Illuminant F6 (lite white fluorescent, 4150 K)
+    F6, (0.37790, 0.38835), (0.38660, 0.37847));
+illuminant!(
+    /// CIE Illuminant F7 (broadband D65 simulator, 6500 K)
+    F7, (0.31292, 0.32933), (0.31569, 0.32960));
+illuminant!(
+    /// CIE Illuminant F8 (broadband D50 simulator, 5000 K)
+    F8, (0.34588, 0.35875), (0.34902, 0.35939));
+illuminant!(
+    /// CIE Illuminant F9 (broadband cool white deluxe, 4150 K)
+    F9, (0.37417, 0.37281), (0.37829, 0.37045));
+illuminant!(
+    /// CIE Illuminant F10 (narrow triband, 5000 K)
+    F10, (0.34609, 0.35986), (0.35090, 0.35444));
+illuminant!(
+    /// CIE Illuminant F11 (narrow triband, 4000 K)
+    F11, (0.38052, 0.37713), (0.38541, 0.37123));
+illuminant!(
+    /// CIE Illuminant F12 (narrow triband, 3000 K)
+    F12, (0.43695, 0.40441), (0.44256, 0.39717));
+
+illuminant!(
+    /// CIE Illuminant LED-B1 (phosphor-converted blue, 2733 K)
+    LedB1, (0.4560, 0.4078));
+illuminant!(
+    /// CIE Illuminant LED-B2 (phosphor-converted blue, 2998 K)
+    LedB2, (0.4357, 0.4012));
+illuminant!(
+    /// CIE Illuminant LED-B3 (phosphor-converted blue, 4103 K)
+    LedB3, (0.3756, 0.3723));
+illuminant!(
+    /// CIE Illuminant LED-B4 (phosphor-converted blue, 5109 K)
+    LedB4, (0.3422, 0.3502));
+illuminant!(
+    /// CIE Illuminant LED-B5 (phosphor-converted blue, 6598 K)
+    LedB5, (0.3118, 0.3236));
+illuminant!(
+    /// CIE Illuminant LED-BH1 (mixing of phosphor-converted blue and red, 2851 K)
+    LedBh1, (0.4474, 0.4066));
+illuminant!(
+    /// CIE Illuminant LED-RGB1 (mixing of red, green and blue LEDs, 2840 K)
+    LedRgb1, (0.4557, 0.4211));
+illuminant!(
+    /// CIE Illuminant LED-V1 (phosphor-converted violet, 2724 K)
+    LedV1, (0.4560, 0.4548));
+illuminant!(
+    /// CIE Illuminant LED-V2 (phosphor-converted violet, 4070 K)
+    LedV2, (0.3781, 0.3775));
+
+illuminant!(
+    /// The DCI-P3 theatrical projector white (not a CIE illuminant, 2° only)
+    Dci, (0.314, 0.351));
+
+
+/// CIE 1931 XYZ colorspace relative to the white point of illuminant I
 /// Normalized to Y=1.0 (Not Y=100 !!!)
 #[derive(Debug, Clone)]
 pub struct Cie1931<I: Illuminant> {
@@ -30,6 +166,11 @@ impl<I: Illuminant> Cie1931<I> {
         }
     }
 
+    /// The white point of the illuminant, at Y=1.0
+    pub fn white() -> Cie1931<I> {
+        Cie1931::new(I::WHITE_XYZ.x as f32, I::WHITE_XYZ.y as f32, I::WHITE_XYZ.z as f32)
+    }
+
     #[inline]
     pub fn x(&self) -> f32 {
         self.v.x
@@ -53,37 +194,7 @@ impl<I: Illuminant> Cie1931<I> {
     }
 }
 
-impl From<Cie1931<D65>> for Cie1931<D50> {
-    fn from(input: Cie1931<D65>) -> Cie1931<D50>
-    {
-        let m: Mat3<f32> = Mat3::new(
-            1.047844353856414, 0.022898981050086, -0.050206647741605,
-            0.029549007606644, 0.990508028941971, -0.017074711360960,
-            -0.009250984365223, 0.015072338237051, 0.751717835079977
-        );
-
-        Cie1931 {
-            v: &m * &input.v,
-            _phantom: Default::default()
-        }
-    }
-}
-
-impl From<Cie1931<D50>> for Cie1931<D65> {
-    fn from(input: Cie1931<D50>) -> Cie1931<D65>
-    {
-        let m: Mat3<f32> = Mat3::new(
-            0.9555491471339036, -0.02305395902610921, 0.0632967285241842,
-            -0.028293615880275732, 1.009916725621172, 0.021049798533869513,
-            0.012326727844429515, -0.020533074478247797, 1.3306432822046876
-        );
-
-        Cie1931 {
-            v: &m * &input.v,
-            _phantom: Default::default()
-        }
-    }
-}
+// Conversions between Cie1931 at different illuminants are in adaptation.rs
 
 
 // CIE 1931xy colorspace
@@ -153,6 +264,27 @@ mod tests {
         assert!(c.v[2] - a.v[2] < 0.000001);
     }
 
+    #[test]
+    fn white_points() {
+        let d65 = Cie1931::<D65>::white();
+        assert!((d65.x() - 0.95046).abs() < 0.00001);
+        assert!((d65.y() - 1.0).abs() < 0.00001);
+        assert!((d65.z() - 1.08906).abs() < 0.00001);
+
+        let a = Cie1931::<A>::white();
+        assert!((a.x() - 1.09850).abs() < 0.00001);
+        assert!((a.z() - 0.35580).abs() < 0.00001);
+
+        // tabulated XYZ, which agree with the tabulated xy to about 5 places
+        assert!((D65::WHITE_XYZ_10.x - 0.94811).abs() < 0.00005);
+        assert!((D65::WHITE_XYZ_10.z - 1.07304).abs() < 0.00005);
+        assert!((D50::WHITE_XYZ_10.x - 0.96720).abs() < 0.00005);
+        assert!((D50::WHITE_XYZ_10.z - 0.81427).abs() < 0.00005);
+
+        assert!((E::WHITE_XYZ.x - 1.0).abs() < 1e-12);
+        assert!((E::WHITE_XYZ.z - 1.0).abs() < 1e-12);
+    }
+
     #[test]
     fn invert_test() {
         let c1 = Cie1931::<D50>::new(0.998123, 0.24987234, 0.45287234);
@@ -0,0 +1,128 @@
+use std::marker::PhantomData;
+use siege_math::{Vec3, Mat3};
+use crate::cie1931::Illuminant10;
+use crate::adaptation::{AdaptationMethod, adaptation_matrix};
+use crate::cmf::CIE1964_10DEG;
+use crate::spd::{Spd, MAX_LUMINOUS_EFFICACY};
+use crate::matrix;
+
+/// CIE 1964 X₁₀Y₁₀Z₁₀ colorspace (the 10° supplementary standard observer)
+/// relative to the white point of illuminant I. Normalized to Y₁₀=1.0.
+///
+/// The 10° observer suits large fields of view, such as skies and big
+/// surfaces. There is no exact conversion from the 2° `Cie1931`, as the
+/// observers differ spectrally; compute both from a spectrum instead.
+#[derive(Debug, Clone)]
+pub struct Cie1964<I: Illuminant10> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant10> Cie1964<I> {
+    pub fn new(x: f32, y: f32, z: f32) -> Cie1964<I> {
+        Cie1964 {
+            v: Vec3::new(x, y, z),
+            _phantom: PhantomData
+        }
+    }
+
+    /// The 10° white point of the illuminant, at Y₁₀=1.0
+    pub fn white() -> Cie1964<I> {
+        Cie1964::new(I::WHITE_XYZ_10.x as f32, I::WHITE_XYZ_10.y as f32,
+                     I::WHITE_XYZ_10.z as f32)
+    }
+
+    #[inline]
+    pub fn x(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn y(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn z(&self) -> f32 {
+        self.v.z
+    }
+
+    pub fn get_luminance(&self) -> f32 {
+        self.v.y
+    }
+
+    pub fn set_luminance(&mut self, luminance: f32) {
+        let scale = luminance / self.v.y;
+        self.v *= scale;
+    }
+
+    /// Chromatically adapt to the 10° white point of another illuminant
+    pub fn adapt<J: Illuminant10>(&self, method: AdaptationMethod) -> Cie1964<J> {
+        let m: Mat3<f32> = matrix::to_mat3(
+            &adaptation_matrix(method, I::WHITE_XYZ_10, J::WHITE_XYZ_10));
+        let v = &m * &self.v;
+        Cie1964::new(v.x, v.y, v.z)
+    }
+}
+
+impl Spd {
+    /// The 10° tristimulus values of an emissive spectrum in W/(sr·m²·nm).
+    /// See `emissive_cie1931`.
+    pub fn emissive_cie1964<I: Illuminant10>(&self) -> Cie1964<I> {
+        let xyz = self.integrate(&CIE1964_10DEG);
+        let k = MAX_LUMINOUS_EFFICACY as f64;
+        Cie1964::new((k * xyz[0]) as f32, (k * xyz[1]) as f32, (k * xyz[2]) as f32)
+    }
+
+    /// The 10° tristimulus values of a reflectance spectrum lit by the
+    /// `illuminant` spectrum. See `reflective_cie1931`.
+    pub fn reflective_cie1964<I: Illuminant10>(&self, illuminant: &Spd) -> Cie1964<I> {
+        let lit = self.clone() * illuminant;
+        let xyz = lit.integrate(&CIE1964_10DEG);
+        let k = 1.0 / illuminant.integrate(&CIE1964_10DEG)[1];
+        Cie1964::new((k * xyz[0]) as f32, (k * xyz[1]) as f32, (k * xyz[2]) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::{Cie1931, A, D50, D60, D65};
+    use crate::colortemp::ColorTemp;
+    use crate::daylight::Daylight;
+
+    #[test]
+    fn test_white_points() {
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        let white: Cie1964<D65> = Spd::constant(1.0).reflective_cie1964(&d65);
+        let tabulated = Cie1964::<D65>::white();
+        assert!((white.x() - tabulated.x()).abs() < 0.001);
+        assert!((white.y() - 1.0).abs() < 0.00001);
+        assert!((white.z() - tabulated.z()).abs() < 0.001);
+
+        // and it differs from the 2° white
+        let white2: Cie1931<D65> = Spd::constant(1.0).reflective_cie1931(&d65);
+        assert!((white.x() - white2.x()).abs() > 0.001);
+
+        let a = Spd::blackbody(ColorTemp::new(2856.0));
+        let white: Cie1964<A> = Spd::constant(1.0).reflective_cie1964(&a);
+        let tabulated = Cie1964::<A>::white();
+        assert!((white.x() - tabulated.x()).abs() < 0.0005);
+        assert!((white.z() - tabulated.z()).abs() < 0.0005);
+
+        // D60 is derived from the daylight spectrum
+        let d60: Spd = From::from(Daylight::from_xy(&crate::cie1931::Cie1931xyY::new(
+            0.32168, 0.33767, 1.0)));
+        let white: Cie1964<D60> = Spd::constant(1.0).reflective_cie1964(&d60);
+        let tabulated = Cie1964::<D60>::white();
+        assert!((white.x() - tabulated.x()).abs() < 0.001);
+        assert!((white.z() - tabulated.z()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_adapt() {
+        let white = Cie1964::<D65>::white().adapt::<D50>(AdaptationMethod::Bradford);
+        let d50 = Cie1964::<D50>::white();
+        assert!((white.x() - d50.x()).abs() < 0.00001);
+        assert!((white.y() - d50.y()).abs() < 0.00001);
+        assert!((white.z() - d50.z()).abs() < 0.00001);
+    }
+}
@@ -1,14 +1,58 @@
 
-use crate::cie1931::Cie1931xyY;
+use crate::cie1931::{Cie1931, Cie1931xyY, D65};
+use crate::luv::Cie1960uv;
+use crate::srgb::LinearSrgb;
+use crate::cmf::{CIE1931_2DEG, cmf_wavelength};
+
+// Planck's radiation constants, c1L = 2hc² (for spectral radiance) and
+// c2 = hc/k as adopted by the CIE (CIE 015:2018)
+const C1L: f64 = 1.191_042_972e-16; // W·m²/sr
+const C2: f64 = 1.4388e-2; // m·K
 
 // Color Temperature in Kelvin
 #[derive(Debug, Clone, Copy)]
-pub struct ColorTemp(pub u16);
+pub struct ColorTemp(pub f32);
 
 impl ColorTemp {
-    pub fn new(k: u16) -> ColorTemp {
+    pub fn new(k: f32) -> ColorTemp {
         ColorTemp(k)
     }
+
+    /// The spectral radiance of a blackbody at this temperature, by Planck's
+    /// law, in W/(sr·m²·nm) at the given wavelength (in nm)
+    pub fn spectral_radiance(&self, wavelength: f32) -> f32 {
+        let lambda = wavelength as f64 * 1e-9;
+        let radiance = C1L / lambda.powi(5) / (C2 / (lambda * self.0 as f64)).exp_m1();
+        (radiance * 1e-9) as f32
+    }
+
+    /// The chromaticity of a blackbody at this temperature, computed exactly
+    /// by integrating Planck's law against the CIE 1931 2° color matching
+    /// functions. Unlike `to_cie1931xyY` this works at any temperature.
+    #[allow(non_snake_case)]
+    pub fn to_cie1931xyY_exact(&self) -> Cie1931xyY {
+        let xyz = planckian_xyz(self.0 as f64);
+        let sum = xyz[0] + xyz[1] + xyz[2];
+        Cie1931xyY::new((xyz[0] / sum) as f32, (xyz[1] / sum) as f32, 1.0)
+    }
+}
+
+// Blackbody tristimulus values at temperature t, in arbitrary units. The
+// spectrum is scaled by its value at the longest wavelength so that it neither
+// overflows nor vanishes at extreme temperatures.
+fn planckian_xyz(t: f64) -> [f64; 3] {
+    let a_min = C2 / (cmf_wavelength(CIE1931_2DEG.len() - 1) as f64 * 1e-9 * t);
+    let mut xyz = [0.0; 3];
+    for (i, cmf) in CIE1931_2DEG.iter().enumerate() {
+        let lambda = cmf_wavelength(i) as f64 * 1e-9;
+        let a = C2 / (lambda * t);
+        // λ⁻⁵ / (eᵃ - 1), times e^a_min
+        let b = (a_min - a).exp() / (-(-a).exp_m1()) / (lambda * 1e6).powi(5);
+        for k in 0..3 {
+            xyz[k] += b * cmf[k] as f64;
+        }
+    }
+    xyz
 }
 
 impl ColorTemp {
@@ -19,10 +63,10 @@ impl ColorTemp {
         // given in https://en.wikipedia.org/wiki/Planckian_locus
 
         // Only works for the given range:
-        if self.0 < 1667 { return None; }
-        if self.0 > 25000 { return None; }
+        if self.0 < 1667.0 { return None; }
+        if self.0 > 25000.0 { return None; }
 
-        let ct = self.0 as f32;
+        let ct = self.0;
 
         let x = if ct < 4000.0 {
             -0.2661239 * (10.0_f32).powi(9) / ct.powi(3)
@@ -56,31 +100,314 @@ impl ColorTemp {
     }
 }
 
+/// A method for estimating correlated color temperature from a chromaticity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CctMethod {
+    /// McCamy's cubic approximation (1992). Fast, and within a few Kelvin
+    /// near the Planckian locus from about 2000 K to 12500 K.
+    McCamy,
+    /// Robertson's interpolation between isotemperature lines (1968)
+    Robertson,
+    /// Ohno's combined triangular and parabolic search (2013). The most
+    /// accurate of these.
+    Ohno,
+}
+
+/// Correlated color temperature, in Kelvin, and Duv, the signed distance
+/// from the Planckian locus in CIE 1960 uv (positive is above the locus,
+/// towards green).
+#[derive(Debug, Clone, Copy)]
+pub struct Cct {
+    pub temperature: f32,
+    pub duv: f32,
+}
+
+impl Cct {
+    pub fn new(temperature: f32, duv: f32) -> Cct {
+        Cct {
+            temperature,
+            duv
+        }
+    }
+
+    /// Estimate the CCT of a CIE 1960 uv chromaticity
+    pub fn from_uv(uv: &Cie1960uv, method: CctMethod) -> Cct {
+        let (u, v) = (uv.u() as f64, uv.v() as f64);
+        let (t, duv) = match method {
+            CctMethod::McCamy => {
+                let xy: Cie1931xyY = From::from(*uv);
+                let t = mccamy(xy.x() as f64, xy.y() as f64);
+                (t, duv_at(u, v, t))
+            },
+            CctMethod::Robertson => robertson(u, v),
+            CctMethod::Ohno => ohno(u, v),
+        };
+        Cct::new(t as f32, duv as f32)
+    }
+
+    /// Estimate the CCT of a CIE 1931 xy chromaticity
+    pub fn from_xy(xy: &Cie1931xyY, method: CctMethod) -> Cct {
+        Cct::from_uv(&From::from(xy.clone()), method)
+    }
+
+    /// Estimate the CCT of a linear sRGB color (such as a white balance
+    /// reference)
+    pub fn from_linear_srgb(srgb: &LinearSrgb, method: CctMethod) -> Cct {
+        let xyz: Cie1931<D65> = From::from(*srgb);
+        Cct::from_uv(&From::from(xyz), method)
+    }
+
+    /// The CIE 1960 uv chromaticity at this CCT and Duv
+    pub fn to_cie1960uv(&self) -> Cie1960uv {
+        let t = self.temperature as f64;
+        let (u, v) = planckian_uv(t);
+        // unit normal to the locus, pointing up (towards +v)
+        let (u2, v2) = planckian_uv(t + 1.0);
+        let (du, dv) = (u2 - u, v2 - v);
+        let len = du.hypot(dv);
+        let duv = self.duv as f64;
+        Cie1960uv::new((u + duv * dv / len) as f32, (v - duv * du / len) as f32)
+    }
+}
+
+// The exact Planckian locus in CIE 1960 uv
+fn planckian_uv(t: f64) -> (f64, f64) {
+    let [x, y, z] = planckian_xyz(t);
+    let divisor = x + 15.0 * y + 3.0 * z;
+    (4.0 * x / divisor, 6.0 * y / divisor)
+}
+
+// Signed distance from the Planckian locus at temperature t
+fn duv_at(u: f64, v: f64, t: f64) -> f64 {
+    let (ut, vt) = planckian_uv(t);
+    (u - ut).hypot(v - vt) * (v - vt).signum()
+}
+
+// McCamy, "Correlated color temperature as an explicit function of
+// chromaticity coordinates", Color Research & Application 17 (1992)
+fn mccamy(x: f64, y: f64) -> f64 {
+    let n = (x - 0.3320) / (y - 0.1858);
+    -449.0 * n * n * n + 3525.0 * n * n - 6823.3 * n + 5520.33
+}
+
+// Isotemperature lines: (reciprocal megakelvin, u, v, slope).
+// From Wyszecki & Stiles, "Color Science" (2nd ed.), table 1(3.11)
+const ROBERTSON: [(f64, f64, f64, f64); 31] = [
+    (0.0, 0.18006, 0.26352, -0.24341),
+    (10.0, 0.18066, 0.26589, -0.25479),
+    (20.0, 0.18133, 0.26846, -0.26876),
+    (30.0, 0.18208, 0.27119, -0.28539),
+    (40.0, 0.18293, 0.27407, -0.30470),
+    (50.0, 0.18388, 0.27709, -0.32675),
+    (60.0, 0.18494, 0.28021, -0.35156),
+    (70.0, 0.18611, 0.28342, -0.37915),
+    (80.0, 0.18740, 0.28668, -0.40955),
+    (90.0, 0.18880, 0.28997, -0.44278),
+    (100.0, 0.19032, 0.29326, -0.47888),
+    (125.0, 0.19462, 0.30141, -0.58204),
+    (150.0, 0.19962, 0.30921, -0.70471),
+    (175.0, 0.20525, 0.31647, -0.84901),
+    (200.0, 0.21142, 0.32312, -1.0182),
+    (225.0, 0.21807, 0.32909, -1.2168),
+    (250.0, 0.22511, 0.33439, -1.4512),
+    (275.0, 0.23247, 0.33904, -1.7298),
+    (300.0, 0.24010, 0.34308, -2.0637),
+    (325.0, 0.24792, 0.34655, -2.4681),
+    (350.0, 0.25591, 0.34951, -2.9641),
+    (375.0, 0.26400, 0.35200, -3.5814),
+    (400.0, 0.27218, 0.35407, -4.3633),
+    (425.0, 0.28039, 0.35577, -5.3762),
+    (450.0, 0.28863, 0.35714, -6.7262),
+    (475.0, 0.29685, 0.35823, -8.5955),
+    (500.0, 0.30505, 0.35907, -11.324),
+    (525.0, 0.31320, 0.35968, -15.628),
+    (550.0, 0.32129, 0.36011, -23.325),
+    (575.0, 0.32931, 0.36038, -40.770),
+    (600.0, 0.33724, 0.36051, -116.45),
+];
+
+// Robertson, "Computation of correlated color temperature and distribution
+// temperature", JOSA 58 (1968)
+fn robertson(u: f64, v: f64) -> (f64, f64) {
+    // distance from each isotemperature line, until the sign changes
+    let distance = |i: usize| -> f64 {
+        let (_, ui, vi, ti) = ROBERTSON[i];
+        ((v - vi) - ti * (u - ui)) / (1.0 + ti * ti).sqrt()
+    };
+
+    let mut i = 1;
+    let mut dm = distance(0);
+    let mut di = distance(1);
+    while i < ROBERTSON.len() - 1 && (di < 0.0) == (dm < 0.0) {
+        i += 1;
+        dm = di;
+        di = distance(i);
+    }
+
+    let (m0, u0, v0, _) = ROBERTSON[i - 1];
+    let (m1, u1, v1, _) = ROBERTSON[i];
+    let p = dm / (dm - di);
+    let mired = m0 + p * (m1 - m0);
+
+    // Duv from the interpolated point on the locus
+    let ul = u0 + p * (u1 - u0);
+    let vl = v0 + p * (v1 - v0);
+    let duv = (u - ul).hypot(v - vl) * (v - vl).signum();
+
+    (1.0e6 / mired, duv)
+}
+
+// Ohno, "Practical use and calculation of CCT and Duv", LEUKOS 10 (2014)
+fn ohno(u: f64, v: f64) -> (f64, f64) {
+    const STEPS: usize = 15;
+
+    let distance = |t: f64| -> f64 {
+        let (ut, vt) = planckian_uv(t);
+        (u - ut).hypot(v - vt)
+    };
+
+    // Cascade: find the nearest of a coarse table of temperatures, then
+    // search again finely between its neighbours
+    let (mut lo, mut hi): (f64, f64) = (500.0, 1.0e6);
+    let mut temps = [0.0; STEPS];
+    let mut dists = [0.0; STEPS];
+    let mut nearest = 0;
+    for _ in 0..8 {
+        let ratio = (hi / lo).powf(1.0 / (STEPS - 1) as f64);
+        for j in 0..STEPS {
+            temps[j] = lo * ratio.powi(j as i32);
+            dists[j] = distance(temps[j]);
+        }
+        nearest = (1..STEPS - 1)
+            .fold(1, |m, j| if dists[j] < dists[m] { j } else { m });
+        lo = temps[nearest - 1];
+        hi = temps[nearest + 1];
+    }
+
+    let (t0, t1, t2) = (temps[nearest - 1], temps[nearest], temps[nearest + 1]);
+    let (d0, d1, d2) = (dists[nearest - 1], dists[nearest], dists[nearest + 1]);
+
+    // Triangular solution. The search is fine enough that Ohno's correction
+    // factor for a 1% table is not needed.
+    let (u0, v0) = planckian_uv(t0);
+    let (u2, v2) = planckian_uv(t2);
+    let l = (u2 - u0).hypot(v2 - v0);
+    let x = (d0 * d0 - d2 * d2 + l * l) / (2.0 * l);
+    let t = t0 + (t2 - t0) * x / l;
+    let vtx = v0 + (v2 - v0) * x / l;
+    let sign = (v - vtx).signum();
+    let duv = (d0 * d0 - x * x).max(0.0).sqrt() * sign;
+
+    if duv.abs() < 0.002 {
+        return (t, duv);
+    }
+
+    // Parabolic solution, further from the locus
+    let xx = (t2 - t1) * (t0 - t2) * (t1 - t0);
+    let a = (t0 * (d2 - d1) + t1 * (d0 - d2) + t2 * (d1 - d0)) / xx;
+    let b = -(t0 * t0 * (d2 - d1) + t1 * t1 * (d0 - d2) + t2 * t2 * (d1 - d0)) / xx;
+    let c = -(d0 * (t2 - t1) * t1 * t2 + d1 * (t0 - t2) * t0 * t2 + d2 * (t1 - t0) * t0 * t1) / xx;
+    let t = -b / (2.0 * a);
+    (t, (a * t * t + b * t + c) * sign)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_planckian_locus() {
-        let ct = ColorTemp::new(2222);
+        let ct = ColorTemp::new(2222.0);
         let xy = ct.to_cie1931xyY().unwrap();
         assert!(xy.v.x > 0.5030);
         assert!(xy.v.x < 0.5035);
         assert!(xy.v.y > 0.4151);
         assert!(xy.v.y < 0.4154);
 
-        let ct = ColorTemp::new(4000);
+        let ct = ColorTemp::new(4000.0);
         let xy = ct.to_cie1931xyY().unwrap();
         assert!(xy.v.x > 0.3802);
         assert!(xy.v.x < 0.3807);
         assert!(xy.v.y > 0.3766);
         assert!(xy.v.y < 0.3769);
 
-        let ct = ColorTemp::new(10000);
+        let ct = ColorTemp::new(10000.0);
         let xy = ct.to_cie1931xyY().unwrap();
         assert!(xy.v.x > 0.2805);
         assert!(xy.v.x < 0.2808);
         assert!(xy.v.y > 0.2882);
         assert!(xy.v.y < 0.2884);
     }
+
+    #[test]
+    fn test_exact_planckian_locus() {
+        // Agrees with the Kim et al. approximation within its range
+        for &k in &[2222.0, 4000.0, 10000.0] {
+            let fit = ColorTemp::new(k).to_cie1931xyY().unwrap();
+            let exact = ColorTemp::new(k).to_cie1931xyY_exact();
+            assert!((fit.x() - exact.x()).abs() < 0.0005);
+            assert!((fit.y() - exact.y()).abs() < 0.0005);
+        }
+
+        // Illuminant A is a 2856 K blackbody
+        let a = ColorTemp::new(2856.0).to_cie1931xyY_exact();
+        assert!((a.x() - 0.44757).abs() < 0.0001);
+        assert!((a.y() - 0.40745).abs() < 0.0001);
+
+        // Embers, and the limit at infinite temperature
+        let embers = ColorTemp::new(900.5).to_cie1931xyY_exact();
+        assert!(embers.x() > 0.65 && embers.x() < 0.74);
+        let hot = ColorTemp::new(1.0e7).to_cie1931xyY_exact();
+        assert!((hot.x() - 0.2399).abs() < 0.0005);
+        assert!((hot.y() - 0.2342).abs() < 0.0005);
+        let cold = ColorTemp::new(100.0).to_cie1931xyY_exact();
+        assert!(cold.x().is_finite() && cold.x() > 0.7);
+    }
+
+    #[test]
+    fn test_spectral_radiance() {
+        // Wien's displacement law: the peak of a 5800 K blackbody is near 500 nm
+        let sun = ColorTemp::new(5800.0);
+        assert!(sun.spectral_radiance(500.0) > sun.spectral_radiance(450.0));
+        assert!(sun.spectral_radiance(500.0) > sun.spectral_radiance(550.0));
+        // 2.69e13 W/(sr·m³) at the peak
+        assert!((sun.spectral_radiance(500.0) - 2.688e4).abs() < 10.0);
+    }
+
+    #[test]
+    fn test_cct() {
+        // D65 is about 6504 K, a little above the locus. Ohno's method gives
+        // 6507 K (as does colour-science), the older methods slightly less.
+        let d65 = Cie1931xyY::new(0.3127, 0.3290, 1.0);
+        for &(method, expected) in &[(CctMethod::McCamy, 6505.0),
+                                     (CctMethod::Robertson, 6504.0),
+                                     (CctMethod::Ohno, 6507.5)] {
+            let cct = Cct::from_xy(&d65, method);
+            assert!((cct.temperature - expected).abs() < 2.0);
+            assert!((cct.duv - 0.0032).abs() < 0.0002);
+        }
+
+        // Illuminant A is 2856 K, on the locus
+        let a = Cie1931xyY::new(0.44757, 0.40745, 1.0);
+        let cct = Cct::from_xy(&a, CctMethod::Ohno);
+        assert!((cct.temperature - 2856.0).abs() < 2.0);
+        assert!(cct.duv.abs() < 0.0001);
+        let cct = Cct::from_xy(&a, CctMethod::Robertson);
+        assert!((cct.temperature - 2856.0).abs() < 5.0);
+
+        let white = LinearSrgb::new(1.0, 1.0, 1.0);
+        let cct = Cct::from_linear_srgb(&white, CctMethod::Ohno);
+        assert!((cct.temperature - 6507.5).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_cct_to_and_from() {
+        // both the triangular (near) and parabolic (far) solutions
+        for &(t, duv) in &[(2700.0, 0.001), (4000.0, -0.01), (9000.0, 0.02)] {
+            let uv = Cct::new(t, duv).to_cie1960uv();
+            let cct = Cct::from_uv(&uv, CctMethod::Ohno);
+            assert!((cct.temperature - t).abs() < t * 0.001);
+            assert!((cct.duv - duv).abs() < 0.0001);
+        }
+    }
 }
@@ -0,0 +1,175 @@
+// CIE daylight: the daylight locus and the D-series illuminant spectra.
+// From CIE 015:2018 "Colorimetry", 4th edition.
+
+use crate::cie1931::Cie1931xyY;
+use crate::colortemp::ColorTemp;
+
+/// The first wavelength of the daylight basis functions, in nm
+pub const DAYLIGHT_START: f32 = 300.0;
+
+/// The wavelength step of the daylight basis functions, in nm
+pub const DAYLIGHT_STEP: f32 = 10.0;
+
+/// The mean daylight spectrum, S0(λ), from 300 nm to 830 nm
+pub const DAYLIGHT_S0: [f32; 54] = [
+    0.04, 6.00, 29.60, 55.30, 57.30, 61.80, // 300
+    61.50, 68.80, 63.40, 65.80, 94.80, 104.80, // 360
+    105.90, 96.80, 113.90, 125.60, 125.50, 121.30, // 420
+    121.30, 113.50, 113.10, 110.80, 106.50, 108.80, // 480
+    105.30, 104.40, 100.00, 96.00, 95.10, 89.10, // 540
+    90.50, 90.30, 88.40, 84.00, 85.10, 81.90, // 600
+    82.60, 84.90, 81.30, 71.90, 74.30, 76.40, // 660
+    63.30, 71.70, 77.00, 65.20, 47.70, 68.60, // 720
+    65.00, 66.00, 61.00, 53.30, 58.90, 61.90, // 780
+];
+
+/// The first characteristic vector of daylight, S1(λ) (yellow-blue variation)
+pub const DAYLIGHT_S1: [f32; 54] = [
+    0.02, 4.50, 22.40, 42.00, 40.60, 41.60, // 300
+    38.00, 42.40, 38.50, 35.00, 43.40, 46.30, // 360
+    43.90, 37.10, 36.70, 35.90, 32.60, 27.90, // 420
+    24.30, 20.10, 16.20, 13.20, 8.60, 6.10, // 480
+    4.20, 1.90, 0.00, -1.60, -3.50, -3.50, // 540
+    -5.80, -7.20, -8.60, -9.50, -10.90, -10.70, // 600
+    -12.00, -14.00, -13.60, -12.00, -13.30, -12.90, // 660
+    -10.60, -11.60, -12.20, -10.20, -7.80, -11.20, // 720
+    -10.40, -10.60, -9.70, -8.30, -9.30, -9.80, // 780
+];
+
+/// The second characteristic vector of daylight, S2(λ) (pink-green variation)
+pub const DAYLIGHT_S2: [f32; 54] = [
+    0.00, 2.00, 4.00, 8.50, 7.80, 6.70, // 300
+    5.30, 6.10, 3.00, 1.20, -1.10, -0.50, // 360
+    -0.70, -1.20, -2.60, -2.90, -2.80, -2.60, // 420
+    -2.60, -1.80, -1.50, -1.30, -1.20, -1.00, // 480
+    -0.50, -0.30, 0.00, 0.20, 0.50, 2.10, // 540
+    3.20, 4.10, 4.70, 5.10, 6.70, 7.30, // 600
+    8.60, 9.80, 10.20, 8.30, 9.60, 8.50, // 660
+    7.00, 7.60, 8.00, 6.70, 5.20, 7.40, // 720
+    6.80, 7.00, 6.40, 5.50, 6.10, 6.50, // 780
+];
+
+impl ColorTemp {
+    /// The chromaticity of CIE daylight at this correlated color temperature.
+    /// Only defined from 4000 K to 25000 K.
+    ///
+    /// Note that the standard D illuminants were defined before the value of
+    /// c2 in Planck's law changed, so D65 is at 6504 K (6500 × 1.4388 / 1.4380).
+    #[allow(non_snake_case)]
+    pub fn to_daylight_cie1931xyY(&self) -> Option<Cie1931xyY> {
+        if self.0 < 4000.0 || self.0 > 25000.0 { return None; }
+
+        let t = self.0 as f64;
+        let x = if t <= 7000.0 {
+            -4.6070e9 / (t * t * t) + 2.9678e6 / (t * t) + 0.09911e3 / t + 0.244063
+        } else {
+            -2.0064e9 / (t * t * t) + 1.9018e6 / (t * t) + 0.24748e3 / t + 0.237040
+        };
+        let y = -3.000 * x * x + 2.870 * x - 0.275;
+
+        Some(Cie1931xyY::new(x as f32, y as f32, 1.0))
+    }
+}
+
+/// A CIE D-series (daylight) illuminant spectrum, for any chromaticity on the
+/// daylight locus. It is relative, normalized to 100 at 560 nm.
+#[derive(Debug, Clone, Copy)]
+pub struct Daylight {
+    m1: f32,
+    m2: f32,
+}
+
+impl Daylight {
+    /// The daylight spectrum at a correlated color temperature from 4000 K to
+    /// 25000 K
+    pub fn new(temperature: ColorTemp) -> Option<Daylight> {
+        temperature.to_daylight_cie1931xyY()
+            .map(|xy| Daylight::from_xy(&xy))
+    }
+
+    /// The daylight spectrum with the given chromaticity, which should be on
+    /// (or near) the daylight locus
+    pub fn from_xy(xy: &Cie1931xyY) -> Daylight {
+        let (x, y) = (xy.x(), xy.y());
+        let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+        Daylight {
+            m1: (-1.3515 - 1.7703 * x + 5.9114 * y) / m,
+            m2: (0.0300 - 31.4424 * x + 30.0717 * y) / m,
+        }
+    }
+
+    /// The weights (M1, M2) of S1 and S2
+    pub fn weights(&self) -> (f32, f32) {
+        (self.m1, self.m2)
+    }
+
+    /// The relative spectral power at `wavelength` (in nm), linearly
+    /// interpolated between the 10 nm samples. Zero outside 300 nm to 830 nm.
+    pub fn spectral_power(&self, wavelength: f32) -> f32 {
+        let f = (wavelength - DAYLIGHT_START) / DAYLIGHT_STEP;
+        if f < 0.0 || f > (DAYLIGHT_S0.len() - 1) as f32 { return 0.0; }
+
+        let i = (f as usize).min(DAYLIGHT_S0.len() - 2);
+        let t = f - i as f32;
+        let lerp = |s: &[f32; 54]| s[i] + (s[i + 1] - s[i]) * t;
+        lerp(&DAYLIGHT_S0) + self.m1 * lerp(&DAYLIGHT_S1) + self.m2 * lerp(&DAYLIGHT_S2)
+    }
+
+    /// The relative spectral power at each of the 10 nm samples from 300 nm to
+    /// 830 nm
+    pub fn samples(&self) -> [f32; 54] {
+        let mut samples = [0.0; 54];
+        for (i, s) in samples.iter_mut().enumerate() {
+            *s = DAYLIGHT_S0[i] + self.m1 * DAYLIGHT_S1[i] + self.m2 * DAYLIGHT_S2[i];
+        }
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmf::{CIE1931_2DEG, cmf_wavelength};
+
+    #[test]
+    fn test_daylight_locus() {
+        let d65 = ColorTemp::new(6504.0).to_daylight_cie1931xyY().unwrap();
+        assert!((d65.x() - 0.3127).abs() < 0.0001);
+        assert!((d65.y() - 0.3291).abs() < 0.0001);
+
+        let d50 = ColorTemp::new(5003.0).to_daylight_cie1931xyY().unwrap();
+        assert!((d50.x() - 0.3457).abs() < 0.0001);
+        assert!((d50.y() - 0.3586).abs() < 0.0001);
+
+        assert!(ColorTemp::new(3000.0).to_daylight_cie1931xyY().is_none());
+        assert!(ColorTemp::new(30000.0).to_daylight_cie1931xyY().is_none());
+    }
+
+    #[test]
+    fn test_d65_spectrum() {
+        let d65 = Daylight::new(ColorTemp::new(6504.0)).unwrap();
+        let (m1, m2) = d65.weights();
+        assert!((m1 - -0.295).abs() < 0.001);
+        assert!((m2 - -0.689).abs() < 0.001);
+
+        // CIE D65 table values
+        assert!((d65.spectral_power(560.0) - 100.0).abs() < 0.0001);
+        assert!((d65.spectral_power(460.0) - 117.812).abs() < 0.05);
+        assert!((d65.spectral_power(300.0) - 0.0341).abs() < 0.001);
+        // interpolated halfway between samples
+        assert!((d65.spectral_power(555.0) - 102.02).abs() < 0.05);
+        assert_eq!(d65.spectral_power(900.0), 0.0);
+        assert!((d65.samples()[16] - d65.spectral_power(460.0)).abs() < 0.0001);
+
+        // integrating the spectrum comes back to the chromaticity
+        let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+        for (i, cmf) in CIE1931_2DEG.iter().enumerate() {
+            let p = d65.spectral_power(cmf_wavelength(i));
+            x += p * cmf[0];
+            y += p * cmf[1];
+            z += p * cmf[2];
+        }
+        assert!((x / (x + y + z) - 0.3127).abs() < 0.0001);
+        assert!((y / (x + y + z) - 0.3290).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,275 @@
+// Color difference metrics.
+//
+// All of these are computed in f64 internally. A difference of about 1.0
+// (2.3 for ΔE*76) is commonly taken as a just noticeable difference.
+
+use std::f64::consts::PI;
+use crate::cie1931::Illuminant;
+use crate::lab::Lab;
+use crate::oklab::Oklab;
+
+/// CIE 1976 color difference, ΔE*ab (euclidean distance in CIELAB)
+pub fn delta_e76<I: Illuminant>(a: &Lab<I>, b: &Lab<I>) -> f32
+{
+    let dl = (a.l() - b.l()) as f64;
+    let da = (a.a() - b.a()) as f64;
+    let db = (a.b() - b.b()) as f64;
+    (dl * dl + da * da + db * db).sqrt() as f32
+}
+
+/// The application-dependent weights of CIE94
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cie94Application {
+    GraphicArts,
+    Textiles,
+}
+
+/// CIE 1994 color difference, ΔE*94.
+///
+/// This is not symmetric; `reference` is the standard and `sample` is compared
+/// to it.
+pub fn delta_e94<I: Illuminant>(reference: &Lab<I>, sample: &Lab<I>,
+                                application: Cie94Application) -> f32
+{
+    let (kl, k1, k2) = match application {
+        Cie94Application::GraphicArts => (1.0, 0.045, 0.015),
+        Cie94Application::Textiles => (2.0, 0.048, 0.014),
+    };
+
+    let (l1, a1, b1) = (reference.l() as f64, reference.a() as f64, reference.b() as f64);
+    let (l2, a2, b2) = (sample.l() as f64, sample.a() as f64, sample.b() as f64);
+
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+
+    let dl = l1 - l2;
+    let dc = c1 - c2;
+    let da = a1 - a2;
+    let db = b1 - b2;
+    // ΔH² = Δa² + Δb² − ΔC², which can come out slightly negative by rounding
+    let dh2 = (da * da + db * db - dc * dc).max(0.0);
+
+    let sl = 1.0;
+    let sc = 1.0 + k1 * c1;
+    let sh = 1.0 + k2 * c1;
+
+    let tl = dl / (kl * sl);
+    let tc = dc / sc;
+    (tl * tl + tc * tc + dh2 / (sh * sh)).sqrt() as f32
+}
+
+/// CIEDE2000 color difference, ΔE00, with the parametric factors kL, kC and kH
+/// all 1.0
+pub fn ciede2000<I: Illuminant>(a: &Lab<I>, b: &Lab<I>) -> f32
+{
+    ciede2000_weighted(a, b, 1.0, 1.0, 1.0)
+}
+
+/// CIEDE2000 color difference, ΔE00, with the given parametric factors.
+///
+/// Follows Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula:
+/// Implementation Notes, Supplementary Test Data, and Mathematical Observations"
+/// (2005).
+pub fn ciede2000_weighted<I: Illuminant>(a: &Lab<I>, b: &Lab<I>,
+                                         kl: f32, kc: f32, kh: f32) -> f32
+{
+    let (l1, a1, b1) = (a.l() as f64, a.a() as f64, a.b() as f64);
+    let (l2, a2, b2) = (b.l() as f64, b.a() as f64, b.b() as f64);
+    let (kl, kc, kh) = (kl as f64, kc as f64, kh as f64);
+
+    let pow7 = |x: f64| -> f64 { x.powi(7) };
+    let twenty_five_7 = pow7(25.0);
+
+    // 1. C'i and h'i
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + twenty_five_7)).sqrt());
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = a1p.hypot(b1);
+    let c2p = a2p.hypot(b2);
+    let hue = |b: f64, ap: f64| -> f64 {
+        if b == 0.0 && ap == 0.0 { return 0.0; }
+        let h = b.atan2(ap).to_degrees();
+        if h < 0.0 { h + 360.0 } else { h }
+    };
+    let h1p = hue(b1, a1p);
+    let h2p = hue(b2, a2p);
+
+    // 2. ΔL', ΔC', ΔH'
+    let dlp = l2 - l1;
+    let dcp = c2p - c1p;
+    let dhp = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let dhp_big = 2.0 * (c1p * c2p).sqrt() * (dhp.to_radians() / 2.0).sin();
+
+    // 3. ΔE00
+    let l_bar_p = (l1 + l2) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar_p = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0
+        - 0.17 * (h_bar_p - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_p).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_p + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_p - 63.0).to_radians().cos();
+
+    let d_theta = 30.0 * (-((h_bar_p - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (pow7(c_bar_p) / (pow7(c_bar_p) + twenty_five_7)).sqrt();
+    let l50 = (l_bar_p - 50.0) * (l_bar_p - 50.0);
+    let sl = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+    let sc = 1.0 + 0.045 * c_bar_p;
+    let sh = 1.0 + 0.015 * c_bar_p * t;
+    let rt = -(2.0 * d_theta * PI / 180.0).sin() * rc;
+
+    let tl = dlp / (kl * sl);
+    let tc = dcp / (kc * sc);
+    let th = dhp_big / (kh * sh);
+
+    (tl * tl + tc * tc + th * th + rt * tc * th).sqrt() as f32
+}
+
+/// CMC l:c color difference. Use l=2, c=1 for acceptability and l=1, c=1 for
+/// perceptibility.
+///
+/// This is not symmetric; `reference` is the standard and `sample` is compared
+/// to it.
+pub fn delta_e_cmc<I: Illuminant>(reference: &Lab<I>, sample: &Lab<I>,
+                                  l: f32, c: f32) -> f32
+{
+    let (l1, a1, b1) = (reference.l() as f64, reference.a() as f64, reference.b() as f64);
+    let (l2, a2, b2) = (sample.l() as f64, sample.a() as f64, sample.b() as f64);
+
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+    let dl = l1 - l2;
+    let dc = c1 - c2;
+    let da = a1 - a2;
+    let db = b1 - b2;
+    let dh2 = (da * da + db * db - dc * dc).max(0.0);
+
+    let mut h1 = b1.atan2(a1).to_degrees();
+    if h1 < 0.0 { h1 += 360.0; }
+
+    let t = if (164.0..=345.0).contains(&h1) {
+        0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+    };
+    let c1_4 = c1.powi(4);
+    let f = (c1_4 / (c1_4 + 1900.0)).sqrt();
+
+    let sl = if l1 < 16.0 { 0.511 } else { 0.040975 * l1 / (1.0 + 0.01765 * l1) };
+    let sc = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+    let sh = sc * (f * t + 1.0 - f);
+
+    let tl = dl / (l as f64 * sl);
+    let tc = dc / (c as f64 * sc);
+    (tl * tl + tc * tc + dh2 / (sh * sh)).sqrt() as f32
+}
+
+/// ΔEOK, the euclidean distance in Oklab.
+///
+/// Oklab L runs from 0 to 1 rather than 0 to 100, so a just noticeable
+/// difference is about 0.02 rather than 2.
+pub fn delta_e_ok(a: &Oklab, b: &Oklab) -> f32
+{
+    let dl = (a.l() - b.l()) as f64;
+    let da = (a.a() - b.a()) as f64;
+    let db = (a.b() - b.b()) as f64;
+    (dl * dl + da * da + db * db).sqrt() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::D65;
+
+    // Sharma, Wu and Dalal (2005), Table 1
+    const SHARMA: [[f32; 7]; 34] = [
+        [50.0000, 2.6772, -79.7751, 50.0000, 0.0000, -82.7485, 2.0425],
+        [50.0000, 3.1571, -77.2803, 50.0000, 0.0000, -82.7485, 2.8615],
+        [50.0000, 2.8361, -74.0200, 50.0000, 0.0000, -82.7485, 3.4412],
+        [50.0000, -1.3802, -84.2814, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -1.1848, -84.8006, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -0.9009, -85.5211, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, 0.0000, 0.0000, 50.0000, -1.0000, 2.0000, 2.3669],
+        [50.0000, -1.0000, 2.0000, 50.0000, 0.0000, 0.0000, 2.3669],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0009, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0010, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0011, 7.2195],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0012, 7.2195],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0009, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0010, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0011, -2.4900, 4.7461],
+        [50.0000, 2.5000, 0.0000, 50.0000, 0.0000, -2.5000, 4.3065],
+        [50.0000, 2.5000, 0.0000, 73.0000, 25.0000, -18.0000, 27.1492],
+        [50.0000, 2.5000, 0.0000, 61.0000, -5.0000, 29.0000, 22.8977],
+        [50.0000, 2.5000, 0.0000, 56.0000, -27.0000, -3.0000, 31.9030],
+        [50.0000, 2.5000, 0.0000, 58.0000, 24.0000, 15.0000, 19.4535],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.1736, 0.5854, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2972, 0.0000, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 1.8634, 0.5757, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2592, 0.3350, 1.0000],
+        [60.2574, -34.0099, 36.2677, 60.4626, -34.1751, 39.4387, 1.2644],
+        [63.0109, -31.0961, -5.8663, 62.8187, -29.7946, -4.0864, 1.2630],
+        [61.2901, 3.7196, -5.3901, 61.4292, 2.2480, -4.9620, 1.8731],
+        [35.0831, -44.1164, 3.7933, 35.0232, -40.0716, 1.5901, 1.8645],
+        [22.7233, 20.0904, -46.6940, 23.0331, 14.9730, -42.5619, 2.0373],
+        [36.4612, 47.8580, 18.3852, 36.2715, 50.5065, 21.2231, 1.4146],
+        [90.8027, -2.0831, 1.4410, 91.1528, -1.6435, 0.0447, 1.4441],
+        [90.9257, -0.5406, -0.9208, 88.6381, -0.8985, -0.7239, 1.5381],
+        [6.7747, -0.2908, -2.4247, 5.8714, -0.0985, -2.2286, 0.6377],
+        [2.0776, 0.0795, -1.1350, 0.9033, -0.0636, -0.5514, 0.9082],
+    ];
+
+    #[test]
+    fn test_ciede2000_sharma() {
+        for row in SHARMA.iter() {
+            let a = Lab::<D65>::new(row[0], row[1], row[2]);
+            let b = Lab::<D65>::new(row[3], row[4], row[5]);
+            let de = ciede2000(&a, &b);
+            assert!((de - row[6]).abs() < 0.0001, "{:?}: {}", row, de);
+            // symmetric
+            let ed = ciede2000(&b, &a);
+            assert!((ed - row[6]).abs() < 0.0001, "{:?}: {}", row, ed);
+        }
+    }
+
+    #[test]
+    fn test_other_metrics() {
+        // Reference values from colour-science
+        let a = Lab::<D65>::new(100.0, 21.5721, 272.2282);
+        let b = Lab::<D65>::new(100.0, 426.6795, 72.39591);
+
+        assert!((delta_e76(&a, &b) - 451.7133).abs() < 0.001);
+        assert!((delta_e94(&a, &b, Cie94Application::GraphicArts) - 83.77923).abs() < 0.001);
+        assert!((delta_e94(&a, &b, Cie94Application::Textiles) - 88.33555).abs() < 0.001);
+        assert!((delta_e_cmc(&a, &b, 2.0, 1.0) - 172.7048).abs() < 0.001);
+        assert!((ciede2000(&a, &b) - 94.03565).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_delta_e_ok() {
+        let a = Oklab::new(0.5, 0.1, -0.1);
+        let b = Oklab::new(0.5, 0.1, -0.1);
+        assert_eq!(delta_e_ok(&a, &b), 0.0);
+
+        let c = Oklab::new(0.53, 0.14, -0.1);
+        assert!((delta_e_ok(&a, &c) - 0.05).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,118 @@
+// Physical camera exposure: from scene luminance in cd/m² to normalized
+// scene-linear values, ready for tone mapping.
+//
+// See ISO 12232:2006, and Lagarde & de Rousiers, "Moving Frostbite to
+// Physically Based Rendering" (2014).
+
+use crate::srgb::LinearSrgb;
+
+/// Reflected-light meter calibration constant, K (cd·s/m²)
+pub const METER_CALIBRATION: f32 = 12.5;
+
+/// How an exposure maps luminance to normalized values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExposureModel {
+    /// Saturation-based sensitivity: the luminance that saturates the sensor
+    /// maps to 1.0
+    SaturationBased,
+    /// Standard output sensitivity: the average luminance maps to 0.18
+    StandardOutputSensitivity,
+}
+
+/// A camera exposure, as an exposure value at ISO 100 (EV100)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    pub ev100: f32,
+}
+
+impl Exposure {
+    pub fn new(ev100: f32) -> Exposure {
+        Exposure {
+            ev100
+        }
+    }
+
+    /// From the aperture f-number N, shutter time t in seconds, and ISO
+    /// sensitivity S: EV100 = log2(N² / t · 100 / S)
+    pub fn from_camera(aperture: f32, shutter_time: f32, iso: f32) -> Exposure {
+        Exposure::new((aperture * aperture / shutter_time * 100.0 / iso).log2())
+    }
+
+    /// The exposure a reflected-light meter would choose for a scene of this
+    /// average luminance in cd/m²: EV100 = log2(L · 100 / K)
+    pub fn from_average_luminance(luminance: f32) -> Exposure {
+        Exposure::new((luminance * 100.0 / METER_CALIBRATION).log2())
+    }
+
+    /// The luminance in cd/m² that saturates the sensor, from
+    /// Lmax = 78 / (S · q) · N² / t with q = 0.65, which is 1.2 · 2^EV100
+    pub fn saturation_luminance(&self) -> f32 {
+        78.0 / (100.0 * 0.65) * 2.0_f32.powf(self.ev100)
+    }
+
+    /// The luminance in cd/m² that is exposed as 18% grey under standard
+    /// output sensitivity, from Lavg = (1000 / 65) · N² / (S · t)
+    pub fn sos_luminance(&self) -> f32 {
+        1000.0 / 65.0 / 100.0 * 2.0_f32.powf(self.ev100)
+    }
+
+    /// The scale from luminance in cd/m² to normalized values
+    pub fn scale(&self, model: ExposureModel) -> f32 {
+        match model {
+            ExposureModel::SaturationBased => 1.0 / self.saturation_luminance(),
+            ExposureModel::StandardOutputSensitivity => 0.18 / self.sos_luminance(),
+        }
+    }
+
+    /// Expose a luminance in cd/m²
+    pub fn expose(&self, luminance: f32, model: ExposureModel) -> f32 {
+        luminance * self.scale(model)
+    }
+
+    /// Expose a linear color whose values are in cd/m²
+    pub fn expose_rgb(&self, input: &LinearSrgb, model: ExposureModel) -> LinearSrgb {
+        let k = self.scale(model);
+        LinearSrgb::new(input.r() * k, input.g() * k, input.b() * k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ev100() {
+        // "sunny 16": f/16, 1/100 s at ISO 100 is about EV 14.6
+        let sunny = Exposure::from_camera(16.0, 1.0 / 100.0, 100.0);
+        assert!((sunny.ev100 - 14.644).abs() < 0.001);
+
+        // doubling the ISO is one stop less light needed
+        let faster = Exposure::from_camera(16.0, 1.0 / 100.0, 200.0);
+        assert!((sunny.ev100 - faster.ev100 - 1.0).abs() < 0.0001);
+
+        // f/1, 1 s at ISO 100 is EV 0
+        assert!(Exposure::from_camera(1.0, 1.0, 100.0).ev100.abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_models() {
+        let exposure = Exposure::new(10.0);
+        assert!((exposure.saturation_luminance() - 1.2 * 1024.0).abs() < 0.001);
+        assert!((exposure.expose(1.2 * 1024.0, ExposureModel::SaturationBased) - 1.0).abs() < 0.00001);
+
+        let lavg = exposure.sos_luminance();
+        assert!((lavg - 157.538).abs() < 0.001);
+        assert!((exposure.expose(lavg, ExposureModel::StandardOutputSensitivity) - 0.18).abs() < 0.00001);
+
+        // a meter calibrated with K = 12.5 exposes the average a little
+        // darker than SOS would
+        let metered = Exposure::from_average_luminance(lavg);
+        let grey = metered.expose(lavg, ExposureModel::StandardOutputSensitivity);
+        assert!((grey - 0.18 * 65.0 * 12.5 / 1000.0).abs() < 0.00001);
+
+        let rgb = exposure.expose_rgb(&LinearSrgb::new(lavg, lavg, 0.0),
+                                      ExposureModel::StandardOutputSensitivity);
+        assert!((rgb.r() - 0.18).abs() < 0.00001);
+        assert_eq!(rgb.b(), 0.0);
+    }
+}
@@ -0,0 +1,226 @@
+// HDR transfer functions of ITU-R BT.2100: Perceptual Quantization (SMPTE
+// ST 2084) and Hybrid Log-Gamma (ARIB STD-B67).
+//
+// Both are parameterized by the reference (diffuse) white in cd/m², which is
+// where linear 1.0 lands. BT.2408 recommends 203 cd/m².
+
+use crate::rec2020::{Rec2020Primaries, LinearRec2020};
+use crate::rgb::{Rgb, TransferFunction};
+
+/// The BT.2408 reference white, in cd/m²
+pub const REFERENCE_WHITE: f32 = 203.0;
+
+// PQ constants
+const M1: f64 = 2610.0 / 16384.0;
+const M2: f64 = 2523.0 / 4096.0 * 128.0;
+const C1: f64 = 3424.0 / 4096.0;
+const C2: f64 = 2413.0 / 4096.0 * 32.0;
+const C3: f64 = 2392.0 / 4096.0 * 32.0;
+
+// The PQ inverse EOTF, from absolute luminance in cd/m² (up to 10000)
+pub(crate) fn pq_encode_nits(luminance: f64) -> f64 {
+    let y = (luminance / 10000.0).clamp(0.0, 1.0).powf(M1);
+    ((C1 + C2 * y) / (1.0 + C3 * y)).powf(M2)
+}
+
+// The PQ EOTF, to absolute luminance in cd/m²
+fn pq_decode_nits(encoded: f64) -> f64 {
+    let e = encoded.clamp(0.0, 1.0).powf(1.0 / M2);
+    ((e - C1).max(0.0) / (C2 - C3 * e)).powf(1.0 / M1) * 10000.0
+}
+
+/// Encode with PQ, where linear 1.0 is `reference_white` cd/m²
+pub fn pq_encode(linear: f32, reference_white: f32) -> f32 {
+    pq_encode_nits(linear as f64 * reference_white as f64) as f32
+}
+
+/// Decode PQ, where linear 1.0 is `reference_white` cd/m²
+pub fn pq_decode(encoded: f32, reference_white: f32) -> f32 {
+    (pq_decode_nits(encoded as f64) / reference_white as f64) as f32
+}
+
+/// The PQ (SMPTE ST 2084) inverse EOTF, encoding absolute luminance up to
+/// 10000 cd/m². Linear 1.0 is `W` cd/m²; with `W` = 1, linear values are
+/// absolute luminance. For a reference white chosen at runtime, use
+/// `pq_encode` and `pq_decode`.
+#[derive(Debug, Clone, Copy)]
+pub struct PqTransfer<const W: u32 = 203>;
+
+impl<const W: u32> TransferFunction for PqTransfer<W> {
+    fn encode(x: f32) -> f32 {
+        pq_encode(x, W as f32)
+    }
+
+    fn decode(x: f32) -> f32 {
+        pq_decode(x, W as f32)
+    }
+}
+
+// HLG constants
+const A: f64 = 0.17883277;
+const B: f64 = 1.0 - 4.0 * A;
+const C: f64 = 0.559_910_729_529_562; // 0.5 - a ln(4a)
+
+// The normalized scene light of linear 1.0, through the inverse of the
+// 1000 cd/m² OOTF (whose system gamma is 1.2)
+fn hlg_scale(reference_white: f32) -> f64 {
+    (reference_white as f64 / 1000.0).powf(1.0 / 1.2)
+}
+
+/// Encode with the HLG OETF, where linear 1.0 is the scene light that a
+/// nominal 1000 cd/m² display shows at `reference_white` cd/m²
+pub fn hlg_encode(linear: f32, reference_white: f32) -> f32 {
+    let e = (linear as f64 * hlg_scale(reference_white)).max(0.0);
+    let signal = if e <= 1.0 / 12.0 { (3.0 * e).sqrt() }
+                 else { A * (12.0 * e - B).ln() + C };
+    signal as f32
+}
+
+/// Decode with the inverse HLG OETF, where linear 1.0 is the scene light
+/// that a nominal 1000 cd/m² display shows at `reference_white` cd/m²
+pub fn hlg_decode(encoded: f32, reference_white: f32) -> f32 {
+    let x = (encoded as f64).max(0.0);
+    let e = if x <= 0.5 { x * x / 3.0 }
+            else { (((x - C) / A).exp() + B) / 12.0 };
+    (e / hlg_scale(reference_white)) as f32
+}
+
+/// The HLG OETF, from scene light to signal. Linear 1.0 is the scene light
+/// that a nominal 1000 cd/m² display shows at `W` cd/m²; with `W` = 1000,
+/// linear values are the normalized scene light E of BT.2100. For a
+/// reference white chosen at runtime, use `hlg_encode` and `hlg_decode`.
+#[derive(Debug, Clone, Copy)]
+pub struct HlgTransfer<const W: u32 = 203>;
+
+impl<const W: u32> TransferFunction for HlgTransfer<W> {
+    fn encode(x: f32) -> f32 {
+        hlg_encode(x, W as f32)
+    }
+
+    fn decode(x: f32) -> f32 {
+        hlg_decode(x, W as f32)
+    }
+}
+
+/// HDR10: Rec.2020 primaries with the PQ transfer function, with linear 1.0
+/// at the 203 cd/m² reference white
+pub type Hdr10St2084 = Rgb<Rec2020Primaries, PqTransfer>;
+
+/// Rec.2100 HLG: Rec.2020 primaries with the HLG transfer function, with
+/// linear 1.0 at the 203 cd/m² reference white
+pub type Rec2100Hlg = Rgb<Rec2020Primaries, HlgTransfer>;
+
+impl From<LinearRec2020> for Hdr10St2084 {
+    fn from(input: LinearRec2020) -> Hdr10St2084 {
+        Hdr10St2084::from_linear(input)
+    }
+}
+
+impl From<Hdr10St2084> for LinearRec2020 {
+    fn from(input: Hdr10St2084) -> LinearRec2020 {
+        input.to_linear()
+    }
+}
+
+impl From<LinearRec2020> for Rec2100Hlg {
+    fn from(input: LinearRec2020) -> Rec2100Hlg {
+        Rec2100Hlg::from_linear(input)
+    }
+}
+
+impl From<Rec2100Hlg> for LinearRec2020 {
+    fn from(input: Rec2100Hlg) -> LinearRec2020 {
+        input.to_linear()
+    }
+}
+
+/// The HLG system gamma for a display of the given peak luminance in cd/m²
+pub fn hlg_system_gamma(peak_luminance: f32) -> f32 {
+    1.2 + 0.42 * (peak_luminance / 1000.0).log10()
+}
+
+/// The HLG OOTF, from normalized scene light E (0..1, as
+/// `HlgTransfer::<1000>` decodes it) to display light in cd/m², for a display
+/// of the given peak luminance with a zero black level
+pub fn hlg_ootf(scene: &LinearRec2020, peak_luminance: f32) -> LinearRec2020 {
+    let gamma = hlg_system_gamma(peak_luminance);
+    let k = peak_luminance * scene.get_luminance().max(0.0).powf(gamma - 1.0);
+    LinearRec2020::new(scene.r() * k, scene.g() * k, scene.b() * k)
+}
+
+/// The inverse of the HLG OOTF, from display light in cd/m² to normalized
+/// scene light
+pub fn hlg_inverse_ootf(display: &LinearRec2020, peak_luminance: f32) -> LinearRec2020 {
+    let gamma = hlg_system_gamma(peak_luminance);
+    let y = (display.get_luminance() / peak_luminance).max(0.0);
+    let k = if y > 0.0 { y.powf((1.0 - gamma) / gamma) / peak_luminance } else { 0.0 };
+    LinearRec2020::new(display.r() * k, display.g() * k, display.b() * k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pq() {
+        // reference values from BT.2100 / BT.2408
+        assert!((PqTransfer::<1>::encode(10000.0) - 1.0).abs() < 0.00001);
+        assert!((PqTransfer::<1>::encode(100.0) - 0.5081).abs() < 0.0001);
+        assert!((PqTransfer::<1>::encode(1000.0) - 0.7518).abs() < 0.0001);
+        assert!(PqTransfer::<1>::encode(0.0).abs() < 0.00001);
+
+        // reference white is linear 1.0, at 58% PQ
+        let white: Hdr10St2084 = From::from(LinearRec2020::new(1.0, 1.0, 1.0));
+        assert!((white.g() - 0.5806).abs() < 0.0005);
+        let back: LinearRec2020 = From::from(white);
+        assert!((back.g() - 1.0).abs() < 0.0001);
+
+        for &x in &[0.0001, 0.01, 0.5, 1.0, 4.0, 49.0] {
+            let y = PqTransfer::<203>::decode(PqTransfer::<203>::encode(x));
+            assert!((x - y).abs() / x < 0.0001);
+        }
+
+        // a fractional reference white, chosen at runtime
+        let white = 80.5;
+        assert!((pq_encode(1.0, white) - PqTransfer::<1>::encode(white)).abs() < 0.000001);
+        assert!((pq_decode(pq_encode(0.3, white), white) - 0.3).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_hlg() {
+        // the OETF: 1/12 of the range is the square root segment at 0.5
+        assert!((HlgTransfer::<1000>::encode(1.0 / 12.0) - 0.5).abs() < 0.00001);
+        assert!((HlgTransfer::<1000>::encode(1.0) - 1.0).abs() < 0.00001);
+
+        // reference white is at 75% HLG
+        let white: Rec2100Hlg = From::from(LinearRec2020::new(1.0, 1.0, 1.0));
+        assert!((white.r() - 0.75).abs() < 0.001);
+        let back: LinearRec2020 = From::from(white);
+        assert!((back.r() - 1.0).abs() < 0.0001);
+
+        for &x in &[0.001, 0.05, 0.5, 1.0, 3.0] {
+            let y = HlgTransfer::<203>::decode(HlgTransfer::<203>::encode(x));
+            assert!((x - y).abs() / x < 0.0001);
+        }
+
+        assert!((hlg_encode(1.0, REFERENCE_WHITE) - 0.75).abs() < 0.001);
+        let white = 100.0;
+        assert!((hlg_decode(hlg_encode(0.3, white), white) - 0.3).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_hlg_ootf() {
+        assert!((hlg_system_gamma(1000.0) - 1.2).abs() < 0.00001);
+
+        // 75% HLG grey is shown at 203 cd/m² on a 1000 cd/m² display
+        let e = HlgTransfer::<1000>::decode(0.75);
+        let display = hlg_ootf(&LinearRec2020::new(e, e, e), 1000.0);
+        assert!((display.g() - 203.0).abs() < 0.5);
+
+        let scene = LinearRec2020::new(0.3, 0.1, 0.05);
+        let display = hlg_ootf(&scene, 2000.0);
+        let back = hlg_inverse_ootf(&display, 2000.0);
+        assert!((back.r() - 0.3).abs() < 0.00001);
+        assert!((back.b() - 0.05).abs() < 0.00001);
+    }
+}
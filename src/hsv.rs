@@ -0,0 +1,323 @@
+use std::marker::PhantomData;
+use siege_math::Vec3;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction};
+
+// Wrap a hue in degrees into [0, 360)
+#[inline]
+fn wrap_hue(h: f32) -> f32 {
+    let h = h.rem_euclid(360.0);
+    // rem_euclid can round up to 360.0 for tiny negative inputs
+    if h >= 360.0 { 0.0 } else { h }
+}
+
+// Hue (degrees), chroma, max and min component of an RGB triplet
+#[inline]
+fn hue_chroma(r: f32, g: f32, b: f32) -> (f32, f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let c = max - min;
+    let h = if c == 0.0 { 0.0 }
+    else if max == r { 60.0 * ((g - b) / c) }
+    else if max == g { 60.0 * ((b - r) / c + 2.0) }
+    else { 60.0 * ((r - g) / c + 4.0) };
+    (wrap_hue(h), c, max, min)
+}
+
+// RGB from hue (degrees), chroma and the amount to add to each component
+#[inline]
+fn from_hue_chroma(h: f32, c: f32, m: f32) -> Vec3<f32> {
+    let hp = h / 60.0;
+    let x = c * (1.0 - (hp.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hp as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Vec3::new(r + m, g + m, b + m)
+}
+
+/// Hue, saturation and value, a cylindrical form of the RGB space `Rgb<S, T>`.
+///
+/// Hue is in degrees, from 0 up to 360 (wrapping). Saturation and value are
+/// from 0.0 to 1.0 for colors within the RGB gamut.
+#[derive(Debug, Clone, Copy)]
+pub struct Hsv<S: RgbPrimaries, T: TransferFunction> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<(S, T)>
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> Hsv<S, T> {
+    pub fn new(h: f32, s: f32, v: f32) -> Hsv<S, T> {
+        Hsv {
+            v: Vec3::new(wrap_hue(h), s, v),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn s(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn v(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Rotate the hue by the given number of degrees
+    pub fn rotate_hue(&self, degrees: f32) -> Hsv<S, T> {
+        Hsv::new(self.h() + degrees, self.s(), self.v())
+    }
+
+    /// Scale the saturation by the given factor, limited to 0.0 to 1.0
+    pub fn scale_saturation(&self, factor: f32) -> Hsv<S, T> {
+        Hsv::new(self.h(), (self.s() * factor).clamp(0.0, 1.0), self.v())
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Rgb<S, T>> for Hsv<S, T> {
+    fn from(input: Rgb<S, T>) -> Hsv<S, T> {
+        let (h, c, max, _) = hue_chroma(input.r(), input.g(), input.b());
+        let s = if max == 0.0 { 0.0 } else { c / max };
+        Hsv::new(h, s, max)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hsv<S, T>> for Rgb<S, T> {
+    fn from(input: Hsv<S, T>) -> Rgb<S, T> {
+        let c = input.v() * input.s();
+        let v = from_hue_chroma(input.h(), c, input.v() - c);
+        Rgb::new(v.x, v.y, v.z)
+    }
+}
+
+/// Hue, saturation and lightness, a cylindrical form of the RGB space
+/// `Rgb<S, T>`.
+///
+/// Hue is in degrees, from 0 up to 360 (wrapping). Saturation and lightness
+/// are from 0.0 to 1.0 for colors within the RGB gamut.
+#[derive(Debug, Clone, Copy)]
+pub struct Hsl<S: RgbPrimaries, T: TransferFunction> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<(S, T)>
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> Hsl<S, T> {
+    pub fn new(h: f32, s: f32, l: f32) -> Hsl<S, T> {
+        Hsl {
+            v: Vec3::new(wrap_hue(h), s, l),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn s(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Rotate the hue by the given number of degrees
+    pub fn rotate_hue(&self, degrees: f32) -> Hsl<S, T> {
+        Hsl::new(self.h() + degrees, self.s(), self.l())
+    }
+
+    /// Scale the saturation by the given factor, limited to 0.0 to 1.0
+    pub fn scale_saturation(&self, factor: f32) -> Hsl<S, T> {
+        Hsl::new(self.h(), (self.s() * factor).clamp(0.0, 1.0), self.l())
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Rgb<S, T>> for Hsl<S, T> {
+    fn from(input: Rgb<S, T>) -> Hsl<S, T> {
+        let (h, c, max, min) = hue_chroma(input.r(), input.g(), input.b());
+        let l = (max + min) / 2.0;
+        let s = if c == 0.0 { 0.0 } else { c / (1.0 - (2.0 * l - 1.0).abs()) };
+        Hsl::new(h, s, l)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hsl<S, T>> for Rgb<S, T> {
+    fn from(input: Hsl<S, T>) -> Rgb<S, T> {
+        let c = (1.0 - (2.0 * input.l() - 1.0).abs()) * input.s();
+        let v = from_hue_chroma(input.h(), c, input.l() - c / 2.0);
+        Rgb::new(v.x, v.y, v.z)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hsv<S, T>> for Hsl<S, T> {
+    fn from(input: Hsv<S, T>) -> Hsl<S, T> {
+        let l = input.v() * (1.0 - input.s() / 2.0);
+        let s = if l == 0.0 || l == 1.0 { 0.0 }
+        else { (input.v() - l) / l.min(1.0 - l) };
+        Hsl::new(input.h(), s, l)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hsl<S, T>> for Hsv<S, T> {
+    fn from(input: Hsl<S, T>) -> Hsv<S, T> {
+        let v = input.l() + input.s() * input.l().min(1.0 - input.l());
+        let s = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - input.l() / v) };
+        Hsv::new(input.h(), s, v)
+    }
+}
+
+/// Hue, whiteness and blackness, a cylindrical form of the RGB space
+/// `Rgb<S, T>`.
+///
+/// Hue is in degrees, from 0 up to 360 (wrapping). Whiteness and blackness are
+/// from 0.0 to 1.0; where they sum to 1.0 or more the color is a grey.
+#[derive(Debug, Clone, Copy)]
+pub struct Hwb<S: RgbPrimaries, T: TransferFunction> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<(S, T)>
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> Hwb<S, T> {
+    pub fn new(h: f32, w: f32, b: f32) -> Hwb<S, T> {
+        Hwb {
+            v: Vec3::new(wrap_hue(h), w, b),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn w(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn b(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Rotate the hue by the given number of degrees
+    pub fn rotate_hue(&self, degrees: f32) -> Hwb<S, T> {
+        Hwb::new(self.h() + degrees, self.w(), self.b())
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hsv<S, T>> for Hwb<S, T> {
+    fn from(input: Hsv<S, T>) -> Hwb<S, T> {
+        Hwb::new(input.h(), (1.0 - input.s()) * input.v(), 1.0 - input.v())
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hwb<S, T>> for Hsv<S, T> {
+    fn from(input: Hwb<S, T>) -> Hsv<S, T> {
+        // normalize greys
+        let (w, b) = if input.w() + input.b() > 1.0 {
+            let sum = input.w() + input.b();
+            (input.w() / sum, input.b() / sum)
+        } else {
+            (input.w(), input.b())
+        };
+        let v = 1.0 - b;
+        let s = if v == 0.0 { 0.0 } else { 1.0 - w / v };
+        Hsv::new(input.h(), s, v)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Rgb<S, T>> for Hwb<S, T> {
+    fn from(input: Rgb<S, T>) -> Hwb<S, T> {
+        let (h, _, max, min) = hue_chroma(input.r(), input.g(), input.b());
+        Hwb::new(h, min, 1.0 - max)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Hwb<S, T>> for Rgb<S, T> {
+    fn from(input: Hwb<S, T>) -> Rgb<S, T> {
+        let hsv: Hsv<S, T> = From::from(input);
+        From::from(hsv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srgb::{Srgb, LinearSrgb};
+
+    #[test]
+    fn test_reference_values() {
+        // (r, g, b), (h, s, v), (h, s, l), (h, w, b)
+        let table = [
+            ((1.0, 0.0, 0.0), (0.0, 1.0, 1.0), (0.0, 1.0, 0.5), (0.0, 0.0, 0.0)),
+            ((0.5, 1.0, 0.5), (120.0, 0.5, 1.0), (120.0, 1.0, 0.75), (120.0, 0.5, 0.0)),
+            ((0.0, 0.0, 0.5), (240.0, 1.0, 0.5), (240.0, 1.0, 0.25), (240.0, 0.0, 0.5)),
+            ((0.75, 0.25, 0.5), (330.0, 2.0 / 3.0, 0.75), (330.0, 0.5, 0.5), (330.0, 0.25, 0.25)),
+            ((0.5, 0.5, 0.5), (0.0, 0.0, 0.5), (0.0, 0.0, 0.5), (0.0, 0.5, 0.5)),
+        ];
+
+        for &((r, g, b), hsv, hsl, hwb) in table.iter() {
+            let rgb = Srgb::new(r, g, b);
+
+            let x: Hsv<_, _> = From::from(rgb);
+            assert!((x.h() - hsv.0).abs() < 0.0001);
+            assert!((x.s() - hsv.1).abs() < 0.0001);
+            assert!((x.v() - hsv.2).abs() < 0.0001);
+
+            let x: Hsl<_, _> = From::from(rgb);
+            assert!((x.h() - hsl.0).abs() < 0.0001);
+            assert!((x.s() - hsl.1).abs() < 0.0001);
+            assert!((x.l() - hsl.2).abs() < 0.0001);
+
+            let x: Hwb<_, _> = From::from(rgb);
+            assert!((x.h() - hwb.0).abs() < 0.0001);
+            assert!((x.w() - hwb.1).abs() < 0.0001);
+            assert!((x.b() - hwb.2).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_to_and_from() {
+        for &(r, g, b) in &[(0.9, 0.2, 0.4), (0.1, 0.7, 0.3), (0.3, 0.3, 0.3),
+                            (0.0, 0.0, 0.0), (1.0, 1.0, 1.0), (0.2, 0.5, 0.95)] {
+            let rgb = LinearSrgb::new(r, g, b);
+
+            let hsv: Hsv<_, _> = From::from(rgb);
+            let hsl: Hsl<_, _> = From::from(hsv);
+            let hwb: Hwb<_, _> = From::from(Hsv::from(hsl));
+
+            for back in &[LinearSrgb::from(hsv), LinearSrgb::from(hsl), LinearSrgb::from(hwb)] {
+                assert!((rgb.r() - back.r()).abs() < 0.00001);
+                assert!((rgb.g() - back.g()).abs() < 0.00001);
+                assert!((rgb.b() - back.b()).abs() < 0.00001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hue_wrap() {
+        let hsv = Hsv::<_, _>::from(Srgb::new(1.0, 0.0, 0.0)).rotate_hue(-30.0);
+        assert!((hsv.h() - 330.0).abs() < 0.0001);
+        let hsv = hsv.rotate_hue(750.0);
+        assert!((hsv.h() - 0.0).abs() < 0.0001);
+
+        let hsl: Hsl<_, _> = Hsl::<_, _>::from(Srgb::new(1.0, 0.0, 0.0))
+            .rotate_hue(120.0);
+        let rgb: Srgb = From::from(hsl);
+        assert!(rgb.r().abs() < 0.00001);
+        assert!((rgb.g() - 1.0).abs() < 0.00001);
+
+        let hsv = Hsv::<_, _>::from(Srgb::new(1.0, 0.5, 0.5)).scale_saturation(0.0);
+        let rgb: Srgb = From::from(hsv);
+        assert!((rgb.r() - rgb.b()).abs() < 0.00001);
+        let hsv = Hsv::<_, _>::from(Srgb::new(1.0, 0.5, 0.5)).scale_saturation(4.0);
+        assert!((hsv.s() - 1.0).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,191 @@
+use std::marker::PhantomData;
+use siege_math::Vec3;
+use crate::cie1931::{Cie1931, Illuminant};
+
+// CIE constants, in their exact rational form
+const EPSILON: f32 = 216.0 / 24389.0;
+const KAPPA: f32 = 24389.0 / 27.0;
+
+/// CIE 1976 L*a*b* (CIELAB) relative to the white point of illuminant I.
+///
+/// L* ranges from 0 (black) to 100 (the reference white).
+#[derive(Debug, Clone, Copy)]
+pub struct Lab<I: Illuminant> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant> Lab<I> {
+    pub fn new(l: f32, a: f32, b: f32) -> Lab<I> {
+        Lab {
+            v: Vec3::new(l, a, b),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn a(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn b(&self) -> f32 {
+        self.v.z
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Lab<I> {
+    fn from(input: Cie1931<I>) -> Lab<I> {
+        let f = |t: f32| -> f32 {
+            if t > EPSILON { t.cbrt() }
+            else { (KAPPA * t + 16.0) / 116.0 }
+        };
+
+        let fx = f(input.x() / I::WHITE_XYZ.x as f32);
+        let fy = f(input.y() / I::WHITE_XYZ.y as f32);
+        let fz = f(input.z() / I::WHITE_XYZ.z as f32);
+
+        Lab::new(
+            116.0 * fy - 16.0,
+            500.0 * (fx - fy),
+            200.0 * (fy - fz)
+        )
+    }
+}
+
+impl<I: Illuminant> From<Lab<I>> for Cie1931<I> {
+    fn from(input: Lab<I>) -> Cie1931<I> {
+        let fy = (input.l() + 16.0) / 116.0;
+        let fx = fy + input.a() / 500.0;
+        let fz = fy - input.b() / 200.0;
+
+        let finv = |f: f32| -> f32 {
+            let f3 = f * f * f;
+            if f3 > EPSILON { f3 }
+            else { (116.0 * f - 16.0) / KAPPA }
+        };
+
+        let y = if input.l() > KAPPA * EPSILON { fy * fy * fy }
+        else { input.l() / KAPPA };
+
+        Cie1931::new(
+            finv(fx) * I::WHITE_XYZ.x as f32,
+            y * I::WHITE_XYZ.y as f32,
+            finv(fz) * I::WHITE_XYZ.z as f32
+        )
+    }
+}
+
+/// CIE LCh(ab), the cylindrical form of CIELAB, relative to the white point of
+/// illuminant I. Hue is in degrees, from 0 up to 360.
+#[derive(Debug, Clone, Copy)]
+pub struct Lch<I: Illuminant> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant> Lch<I> {
+    pub fn new(l: f32, c: f32, h: f32) -> Lch<I> {
+        Lch {
+            v: Vec3::new(l, c, h),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn c(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.z
+    }
+}
+
+impl<I: Illuminant> From<Lab<I>> for Lch<I> {
+    fn from(input: Lab<I>) -> Lch<I> {
+        let c = input.a().hypot(input.b());
+        let mut h = input.b().atan2(input.a()).to_degrees();
+        if h < 0.0 { h += 360.0; }
+        Lch::new(input.l(), c, h)
+    }
+}
+
+impl<I: Illuminant> From<Lch<I>> for Lab<I> {
+    fn from(input: Lch<I>) -> Lab<I> {
+        let (sin, cos) = input.h().to_radians().sin_cos();
+        Lab::new(input.l(), input.c() * cos, input.c() * sin)
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Lch<I> {
+    fn from(input: Cie1931<I>) -> Lch<I> {
+        let lab: Lab<I> = From::from(input);
+        From::from(lab)
+    }
+}
+
+impl<I: Illuminant> From<Lch<I>> for Cie1931<I> {
+    fn from(input: Lch<I>) -> Cie1931<I> {
+        let lab: Lab<I> = From::from(input);
+        From::from(lab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::{D50, D65};
+
+    #[test]
+    fn test_xyz_to_lab() {
+        // Reference from colour-science (XYZ_to_Lab, D65 at 0.3127, 0.3290)
+        let xyz = Cie1931::<D65>::new(0.20654008, 0.12197225, 0.05136952);
+        let lab: Lab<D65> = From::from(xyz);
+        assert!((lab.l() - 41.52788).abs() < 0.001);
+        assert!((lab.a() - 52.63858).abs() < 0.001);
+        assert!((lab.b() - 26.92318).abs() < 0.001);
+
+        let lch: Lch<D65> = From::from(lab);
+        assert!((lch.l() - 41.52788).abs() < 0.001);
+        assert!((lch.c() - 59.12426).abs() < 0.001);
+        assert!((lch.h() - 27.08849).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_white_and_black() {
+        let white: Lab<D50> = From::from(Cie1931::<D50>::white());
+        assert!((white.l() - 100.0).abs() < 0.0001);
+        assert!(white.a().abs() < 0.0001);
+        assert!(white.b().abs() < 0.0001);
+
+        let black: Lab<D50> = From::from(Cie1931::<D50>::new(0.0, 0.0, 0.0));
+        assert!(black.l().abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_to_and_from() {
+        // above and below the linear segment
+        for xyz in &[Cie1931::<D65>::new(0.3, 0.4, 0.5),
+                     Cie1931::<D65>::new(0.003, 0.004, 0.005)] {
+            let lch: Lch<D65> = From::from(xyz.clone());
+            let back: Cie1931<D65> = From::from(lch);
+            assert!((xyz.x() - back.x()).abs() < 0.00001);
+            assert!((xyz.y() - back.y()).abs() < 0.00001);
+            assert!((xyz.z() - back.z()).abs() < 0.00001);
+        }
+    }
+
+    #[test]
+    fn test_hue_range() {
+        let lch: Lch<D65> = From::from(Lab::<D65>::new(50.0, 10.0, -10.0));
+        assert!((lch.h() - 315.0).abs() < 0.0001);
+    }
+}
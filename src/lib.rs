@@ -1,22 +1,68 @@
 
 pub mod chromaticities;
+pub mod rgb;
+pub mod hsv;
 pub mod colortemp;
+pub mod cmf;
+pub mod daylight;
+pub mod spd;
+pub mod upsampling;
 pub mod cie1931;
+pub mod cie1964;
+pub mod adaptation;
+pub mod lab;
+pub mod luv;
+pub mod oklab;
+pub mod delta_e;
+pub mod cam;
 pub mod srgb;
 pub mod lms;
 pub mod aces;
+pub mod aces_output;
+pub mod tonemap;
+pub mod exposure;
+pub mod photometry;
 pub mod rec2020;
+pub mod rec709;
+pub mod p3;
+pub mod adobe_rgb;
+pub mod prophoto;
+pub mod hdr;
+mod matrix;
 
 pub use crate::chromaticities::*;
+pub use crate::rgb::*;
+pub use crate::hsv::*;
 pub use crate::colortemp::*;
+pub use crate::cmf::*;
+pub use crate::daylight::*;
+pub use crate::spd::*;
+pub use crate::upsampling::*;
 pub use crate::cie1931::*;
+pub use crate::cie1964::*;
+pub use crate::adaptation::*;
+pub use crate::lab::*;
+pub use crate::luv::*;
+pub use crate::oklab::*;
+pub use crate::delta_e::*;
+pub use crate::cam::*;
 pub use crate::srgb::*;
 pub use crate::lms::*;
 pub use crate::aces::*;
+pub use crate::aces_output::*;
+pub use crate::tonemap::*;
+pub use crate::exposure::*;
+pub use crate::photometry::*;
 pub use crate::rec2020::*;
+pub use crate::rec709::*;
+pub use crate::p3::*;
+pub use crate::adobe_rgb::*;
+pub use crate::prophoto::*;
+pub use crate::hdr::*;
 
 // Converts watts-or-lumens/sq-meter to an RGB value from 0.0 to 1.0
 // using the given white_point, and presuming a 100,000:1 contrast ratio
+#[deprecated(note = "use `Exposure` to expose luminance, then a `ToneMap`")]
 pub fn color_level(irradiance: f32, white_point: f32) -> f32
 {
     // We simulate the human eye with 100,000:1 contrast ratio,
@@ -1,17 +1,91 @@
 
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use siege_math::{Vec3, Mat3};
-use crate::cie1931::{Cie1931, D65};
+use crate::cie1931::{Cie1931, Illuminant, D65};
+use crate::adaptation::AdaptationMethod;
+use crate::matrix::{self, M3};
 
-/// This is the colorspace to use when converting white points
+/// A set of cone fundamentals: the response of the long, medium and short
+/// wavelength cones, as a linear transform of CIE XYZ.
+///
+/// Implement this on a marker type to declare a new cone space.
+pub trait ConeFundamentals: Debug + Clone + Copy {
+    /// CIE 1931 XYZ to LMS
+    const XYZ_TO_LMS: M3;
+
+    /// LMS to CIE 1931 XYZ
+    const LMS_TO_XYZ: M3 = matrix::invert(&Self::XYZ_TO_LMS);
+}
+
+/// Hunt-Pointer-Estevez cones, normalized to equal energy (as in CIECAM02)
+#[derive(Debug, Clone, Copy)]
+pub struct Hpe;
+
+impl ConeFundamentals for Hpe {
+    const XYZ_TO_LMS: M3 = [
+        [0.38971, 0.68898, -0.07868],
+        [-0.22981, 1.18340, 0.04641],
+        [0.0, 0.0, 1.0],
+    ];
+}
+
+/// The Bradford "sharpened" cone space
+#[derive(Debug, Clone, Copy)]
+pub struct Bradford;
+
+impl ConeFundamentals for Bradford {
+    const XYZ_TO_LMS: M3 = AdaptationMethod::Bradford.cone_response();
+}
+
+/// The CIECAM02 (CAT02) sharpened cone space
+#[derive(Debug, Clone, Copy)]
+pub struct Cat02;
+
+impl ConeFundamentals for Cat02 {
+    const XYZ_TO_LMS: M3 = AdaptationMethod::Cat02.cone_response();
+}
+
+/// The CAM16 (CAT16) sharpened cone space
+#[derive(Debug, Clone, Copy)]
+pub struct Cat16;
+
+impl ConeFundamentals for Cat16 {
+    const XYZ_TO_LMS: M3 = AdaptationMethod::Cat16.cone_response();
+}
+
+/// The physiological Stockman & Sharpe 2° cone fundamentals, as adopted in
+/// CIE 170-1:2006.
+///
+/// The matrix is the CIE 170-2 transform from these cones to the 2006
+/// physiological XYZ, which only approximates CIE 1931 XYZ.
+#[derive(Debug, Clone, Copy)]
+pub struct StockmanSharpe;
+
+impl ConeFundamentals for StockmanSharpe {
+    const LMS_TO_XYZ: M3 = [
+        [1.94735469, -1.41445123, 0.36476327],
+        [0.68990272, 0.34832189, 0.0],
+        [0.0, 0.0, 1.93485343],
+    ];
+    const XYZ_TO_LMS: M3 = matrix::invert(&Self::LMS_TO_XYZ);
+}
+
+/// LMS cone response colorspace, with cone fundamentals C, relative to the
+/// white point of illuminant I.
+///
+/// This is the colorspace to use when converting white points.
 #[derive(Debug, Clone)]
-pub struct Lms {
-    pub v: Vec3<f32>
+pub struct Lms<C: ConeFundamentals = Cat02, I: Illuminant = D65> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<(C, I)>
 }
 
-impl Lms {
-    pub fn new(x: f32, y: f32, z: f32) -> Lms {
+impl<C: ConeFundamentals, I: Illuminant> Lms<C, I> {
+    pub fn new(x: f32, y: f32, z: f32) -> Lms<C, I> {
         Lms {
-            v: Vec3::new(x, y, z)
+            v: Vec3::new(x, y, z),
+            _phantom: PhantomData
         }
     }
 
@@ -27,42 +101,64 @@ impl Lms {
     pub fn z(&self) -> f32 {
         self.v.z
     }
+
+    /// The long wavelength cone response (same as x)
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    /// The medium wavelength cone response (same as y)
+    #[inline]
+    pub fn m(&self) -> f32 {
+        self.v.y
+    }
+    /// The short wavelength cone response (same as z)
+    #[inline]
+    pub fn s(&self) -> f32 {
+        self.v.z
+    }
+
+    /// The cone response to the white point of the illuminant
+    pub fn white() -> Lms<C, I> {
+        From::from(Cie1931::<I>::white())
+    }
+
+    /// von Kries adaptation to the white point of another illuminant, scaling
+    /// each cone response by the ratio of the whites
+    pub fn adapt<J: Illuminant>(&self) -> Lms<C, J> {
+        let (src, dst) = (Lms::<C, I>::white(), Lms::<C, J>::white());
+        Lms::new(self.l() * dst.l() / src.l(),
+                 self.m() * dst.m() / src.m(),
+                 self.s() * dst.s() / src.s())
+    }
 }
 
-impl From<Cie1931<D65>> for Lms {
-    fn from(input: Cie1931<D65>) -> Lms {
-        // CIECAM02
-        let m: Mat3<f32> = Mat3::new(
-            0.7328, 0.4296, -0.1624,
-            -0.7036, 1.6975, 0.0061,
-            0.0030, 0.0136, 0.9834
-        );
+impl<C: ConeFundamentals, I: Illuminant> From<Cie1931<I>> for Lms<C, I> {
+    fn from(input: Cie1931<I>) -> Lms<C, I> {
+        let m: Mat3<f32> = matrix::to_mat3(&C::XYZ_TO_LMS);
         Lms {
-            v: &m * &input.v
+            v: &m * &input.v,
+            _phantom: PhantomData
         }
     }
 }
 
-impl From<Lms> for Cie1931<D65> {
-    fn from(input: Lms) -> Cie1931<D65> {
-        // Inverse CIECAM02
-        let m: Mat3<f32> = Mat3::new(
-            1.0961238, -0.278869, 0.18274519,
-            0.45436904,  0.47353318, 0.07209781,
-            -0.0096276095, -0.0056980313, 1.0153257
-        );
+impl<C: ConeFundamentals, I: Illuminant> From<Lms<C, I>> for Cie1931<I> {
+    fn from(input: Lms<C, I>) -> Cie1931<I> {
+        let m: Mat3<f32> = matrix::to_mat3(&C::LMS_TO_XYZ);
         let v = &m * &input.v;
-        Cie1931::<D65>::new(v.x, v.y, v.z)
+        Cie1931::<I>::new(v.x, v.y, v.z)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cie1931::{A, D50};
 
     #[test]
     fn lms_to_from() {
-        let a = Lms::new(0.123, 1.0, 0.234);
+        let a: Lms = Lms::new(0.123, 1.0, 0.234);
         let b: Cie1931<D65> = From::from(a.clone());
         let c: Lms = From::from(b);
 
@@ -73,4 +169,43 @@ mod tests {
         assert!(a.v.z - c.v.z < 0.000001);
         assert!(c.v.z - a.v.z < 0.000001);
     }
+
+    #[test]
+    fn test_cone_spaces() {
+        fn round_trip<C: ConeFundamentals>() {
+            let xyz = Cie1931::<D50>::new(0.3, 0.4, 0.5);
+            let lms: Lms<C, D50> = From::from(xyz.clone());
+            let back: Cie1931<D50> = From::from(lms);
+            assert!((xyz.x() - back.x()).abs() < 0.00001);
+            assert!((xyz.y() - back.y()).abs() < 0.00001);
+            assert!((xyz.z() - back.z()).abs() < 0.00001);
+        }
+        round_trip::<Hpe>();
+        round_trip::<Bradford>();
+        round_trip::<Cat02>();
+        round_trip::<Cat16>();
+        round_trip::<StockmanSharpe>();
+
+        // Equal energy white gives equal HPE cone responses
+        let e: Lms<Hpe, crate::cie1931::E> = Lms::white();
+        assert!((e.l() - 1.0).abs() < 0.0001);
+        assert!((e.m() - 1.0).abs() < 0.0001);
+        assert!((e.s() - 1.0).abs() < 0.0001);
+
+        // Physiological cones: luminance is a weighted sum of L and M
+        let ss: Lms<StockmanSharpe, D65> = Lms::white();
+        assert!((0.6899027 * ss.l() + 0.34832189 * ss.m() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_adapt() {
+        // von Kries in Bradford cones is Bradford adaptation
+        let xyz = Cie1931::<A>::new(0.3, 0.4, 0.5);
+        let lms: Lms<Bradford, A> = From::from(xyz.clone());
+        let adapted: Cie1931<D65> = From::from(lms.adapt::<D65>());
+        let expected: Cie1931<D65> = From::from(xyz);
+        assert!((adapted.x() - expected.x()).abs() < 0.0001);
+        assert!((adapted.y() - expected.y()).abs() < 0.0001);
+        assert!((adapted.z() - expected.z()).abs() < 0.0001);
+    }
 }
@@ -0,0 +1,320 @@
+use std::marker::PhantomData;
+use siege_math::{Vec2, Vec3};
+use crate::cie1931::{Cie1931, Cie1931xyY, Illuminant};
+
+// CIE constants, in their exact rational form
+const EPSILON: f32 = 216.0 / 24389.0;
+const KAPPA: f32 = 24389.0 / 27.0;
+
+/// CIE 1976 UCS chromaticity coordinates (u′, v′)
+#[derive(Debug, Clone, Copy)]
+pub struct Cie1976uv {
+    pub uv: Vec2<f32>
+}
+
+impl Cie1976uv {
+    pub fn new(u: f32, v: f32) -> Cie1976uv {
+        Cie1976uv {
+            uv: Vec2 { x: u, y: v }
+        }
+    }
+
+    #[inline]
+    pub fn u(&self) -> f32 {
+        self.uv.x
+    }
+    #[inline]
+    pub fn v(&self) -> f32 {
+        self.uv.y
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Cie1976uv {
+    fn from(input: Cie1931<I>) -> Cie1976uv {
+        let mut divisor = input.x() + 15.0 * input.y() + 3.0 * input.z();
+        if divisor==0.0 { divisor = 1e-10; }
+        Cie1976uv::new(
+            4.0 * input.x() / divisor,
+            9.0 * input.y() / divisor
+        )
+    }
+}
+
+impl From<Cie1931xyY> for Cie1976uv {
+    fn from(input: Cie1931xyY) -> Cie1976uv {
+        let divisor = -2.0 * input.x() + 12.0 * input.y() + 3.0;
+        Cie1976uv::new(
+            4.0 * input.x() / divisor,
+            9.0 * input.y() / divisor
+        )
+    }
+}
+
+// The luminance is lost, and comes back as Y=1.0
+impl From<Cie1976uv> for Cie1931xyY {
+    fn from(input: Cie1976uv) -> Cie1931xyY {
+        let divisor = 6.0 * input.u() - 16.0 * input.v() + 12.0;
+        Cie1931xyY::new(
+            9.0 * input.u() / divisor,
+            4.0 * input.v() / divisor,
+            1.0
+        )
+    }
+}
+
+/// CIE 1960 UCS chromaticity coordinates (u, v). These are still used for
+/// correlated color temperature: u = u′ and v = ⅔v′
+#[derive(Debug, Clone, Copy)]
+pub struct Cie1960uv {
+    pub uv: Vec2<f32>
+}
+
+impl Cie1960uv {
+    pub fn new(u: f32, v: f32) -> Cie1960uv {
+        Cie1960uv {
+            uv: Vec2 { x: u, y: v }
+        }
+    }
+
+    #[inline]
+    pub fn u(&self) -> f32 {
+        self.uv.x
+    }
+    #[inline]
+    pub fn v(&self) -> f32 {
+        self.uv.y
+    }
+}
+
+impl From<Cie1976uv> for Cie1960uv {
+    fn from(input: Cie1976uv) -> Cie1960uv {
+        Cie1960uv::new(input.u(), input.v() * 2.0 / 3.0)
+    }
+}
+
+impl From<Cie1960uv> for Cie1976uv {
+    fn from(input: Cie1960uv) -> Cie1976uv {
+        Cie1976uv::new(input.u(), input.v() * 1.5)
+    }
+}
+
+impl From<Cie1931xyY> for Cie1960uv {
+    fn from(input: Cie1931xyY) -> Cie1960uv {
+        let uv: Cie1976uv = From::from(input);
+        From::from(uv)
+    }
+}
+
+// The luminance is lost, and comes back as Y=1.0
+impl From<Cie1960uv> for Cie1931xyY {
+    fn from(input: Cie1960uv) -> Cie1931xyY {
+        let uv: Cie1976uv = From::from(input);
+        From::from(uv)
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Cie1960uv {
+    fn from(input: Cie1931<I>) -> Cie1960uv {
+        let uv: Cie1976uv = From::from(input);
+        From::from(uv)
+    }
+}
+
+/// CIE 1976 L*u*v* (CIELUV) relative to the white point of illuminant I.
+///
+/// L* ranges from 0 (black) to 100 (the reference white). Unlike CIELAB,
+/// additive mixtures of light lie on straight lines in u′v′ (and so in the
+/// chromaticity of L*u*v*).
+#[derive(Debug, Clone, Copy)]
+pub struct Luv<I: Illuminant> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant> Luv<I> {
+    pub fn new(l: f32, u: f32, v: f32) -> Luv<I> {
+        Luv {
+            v: Vec3::new(l, u, v),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn u(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn v(&self) -> f32 {
+        self.v.z
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Luv<I> {
+    fn from(input: Cie1931<I>) -> Luv<I> {
+        let yr = input.y() / I::WHITE_XYZ.y as f32;
+        let l = if yr > EPSILON { 116.0 * yr.cbrt() - 16.0 }
+        else { KAPPA * yr };
+
+        if l == 0.0 {
+            return Luv::new(0.0, 0.0, 0.0);
+        }
+
+        let uv: Cie1976uv = From::from(input);
+        let white: Cie1976uv = From::from(Cie1931::<I>::white());
+
+        Luv::new(
+            l,
+            13.0 * l * (uv.u() - white.u()),
+            13.0 * l * (uv.v() - white.v())
+        )
+    }
+}
+
+impl<I: Illuminant> From<Luv<I>> for Cie1931<I> {
+    fn from(input: Luv<I>) -> Cie1931<I> {
+        if input.l() <= 0.0 {
+            return Cie1931::new(0.0, 0.0, 0.0);
+        }
+
+        let white: Cie1976uv = From::from(Cie1931::<I>::white());
+        let u = input.u() / (13.0 * input.l()) + white.u();
+        let v = input.v() / (13.0 * input.l()) + white.v();
+
+        let y = if input.l() > KAPPA * EPSILON { ((input.l() + 16.0) / 116.0).powi(3) }
+        else { input.l() / KAPPA } * I::WHITE_XYZ.y as f32;
+
+        Cie1931::new(
+            y * 9.0 * u / (4.0 * v),
+            y,
+            y * (12.0 - 3.0 * u - 20.0 * v) / (4.0 * v)
+        )
+    }
+}
+
+/// CIE LCh(uv), the cylindrical form of CIELUV, relative to the white point of
+/// illuminant I. Hue is in degrees, from 0 up to 360.
+#[derive(Debug, Clone, Copy)]
+pub struct Lchuv<I: Illuminant> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<I>
+}
+
+impl<I: Illuminant> Lchuv<I> {
+    pub fn new(l: f32, c: f32, h: f32) -> Lchuv<I> {
+        Lchuv {
+            v: Vec3::new(l, c, h),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn c(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Saturation, s(uv) = C*(uv) / L*
+    pub fn saturation(&self) -> f32 {
+        if self.l() == 0.0 { 0.0 } else { self.c() / self.l() }
+    }
+}
+
+impl<I: Illuminant> From<Luv<I>> for Lchuv<I> {
+    fn from(input: Luv<I>) -> Lchuv<I> {
+        let c = input.u().hypot(input.v());
+        let mut h = input.v().atan2(input.u()).to_degrees();
+        if h < 0.0 { h += 360.0; }
+        Lchuv::new(input.l(), c, h)
+    }
+}
+
+impl<I: Illuminant> From<Lchuv<I>> for Luv<I> {
+    fn from(input: Lchuv<I>) -> Luv<I> {
+        let (sin, cos) = input.h().to_radians().sin_cos();
+        Luv::new(input.l(), input.c() * cos, input.c() * sin)
+    }
+}
+
+impl<I: Illuminant> From<Cie1931<I>> for Lchuv<I> {
+    fn from(input: Cie1931<I>) -> Lchuv<I> {
+        let luv: Luv<I> = From::from(input);
+        From::from(luv)
+    }
+}
+
+impl<I: Illuminant> From<Lchuv<I>> for Cie1931<I> {
+    fn from(input: Lchuv<I>) -> Cie1931<I> {
+        let luv: Luv<I> = From::from(input);
+        From::from(luv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::D65;
+
+    #[test]
+    fn test_xyz_to_luv() {
+        // Reference from colour-science (XYZ_to_Luv, D65 at 0.3127, 0.3290)
+        let xyz = Cie1931::<D65>::new(0.20654008, 0.12197225, 0.05136952);
+
+        let uv: Cie1976uv = From::from(xyz.clone());
+        assert!((uv.u() - 0.37720213).abs() < 0.000001);
+        assert!((uv.v() - 0.50120264).abs() < 0.000001);
+
+        let uv60: Cie1960uv = From::from(xyz.clone());
+        assert!((uv60.u() - 0.37720213).abs() < 0.000001);
+        assert!((uv60.v() - 0.3341351).abs() < 0.000001);
+
+        let luv: Luv<D65> = From::from(xyz);
+        assert!((luv.l() - 41.52788).abs() < 0.001);
+        assert!((luv.u() - 96.83626).abs() < 0.001);
+        assert!((luv.v() - 17.7521).abs() < 0.001);
+
+        let lch: Lchuv<D65> = From::from(luv);
+        assert!((lch.c() - 98.44998).abs() < 0.001);
+        assert!((lch.h() - 10.38816).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_xy_uv() {
+        // D65 in u'v'
+        let xy = Cie1931xyY::new(0.3127, 0.3290, 1.0);
+        let uv: Cie1976uv = From::from(xy);
+        assert!((uv.u() - 0.19783).abs() < 0.00001);
+        assert!((uv.v() - 0.46833).abs() < 0.00001);
+
+        let xy2: Cie1931xyY = From::from(uv);
+        assert!((xy2.x() - 0.3127).abs() < 0.000001);
+        assert!((xy2.y() - 0.3290).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_to_and_from() {
+        for xyz in &[Cie1931::<D65>::new(0.3, 0.4, 0.5),
+                     Cie1931::<D65>::new(0.003, 0.004, 0.005)] {
+            let lch: Lchuv<D65> = From::from(xyz.clone());
+            let back: Cie1931<D65> = From::from(lch);
+            assert!((xyz.x() - back.x()).abs() < 0.00001);
+            assert!((xyz.y() - back.y()).abs() < 0.00001);
+            assert!((xyz.z() - back.z()).abs() < 0.00001);
+        }
+
+        let white: Luv<D65> = From::from(Cie1931::<D65>::white());
+        assert!((white.l() - 100.0).abs() < 0.0001);
+        assert!(white.u().abs() < 0.0001);
+        assert!(white.v().abs() < 0.0001);
+    }
+}
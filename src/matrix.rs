@@ -0,0 +1,75 @@
+// Small 3x3 matrix helpers in f64, usable in const contexts so that derived
+// matrices can be computed once at compile time.
+
+use siege_math::Mat3;
+
+pub(crate) type M3 = [[f64; 3]; 3];
+
+pub(crate) const fn mul(a: &M3, b: &M3) -> M3 {
+    let mut out = [[0.0; 3]; 3];
+    let mut r = 0;
+    while r < 3 {
+        let mut c = 0;
+        while c < 3 {
+            out[r][c] = a[r][0] * b[0][c] + a[r][1] * b[1][c] + a[r][2] * b[2][c];
+            c += 1;
+        }
+        r += 1;
+    }
+    out
+}
+
+pub(crate) const fn mul_vec(a: &M3, v: [f64; 3]) -> [f64; 3] {
+    [
+        a[0][0] * v[0] + a[0][1] * v[1] + a[0][2] * v[2],
+        a[1][0] * v[0] + a[1][1] * v[1] + a[1][2] * v[2],
+        a[2][0] * v[0] + a[2][1] * v[1] + a[2][2] * v[2],
+    ]
+}
+
+pub(crate) const fn diag(v: [f64; 3]) -> M3 {
+    [
+        [v[0], 0.0, 0.0],
+        [0.0, v[1], 0.0],
+        [0.0, 0.0, v[2]],
+    ]
+}
+
+pub(crate) const fn invert(m: &M3) -> M3 {
+    // cofactors
+    let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+    let c01 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+    let c02 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+    let c10 = m[0][2] * m[2][1] - m[0][1] * m[2][2];
+    let c11 = m[0][0] * m[2][2] - m[0][2] * m[2][0];
+    let c12 = m[0][1] * m[2][0] - m[0][0] * m[2][1];
+    let c20 = m[0][1] * m[1][2] - m[0][2] * m[1][1];
+    let c21 = m[0][2] * m[1][0] - m[0][0] * m[1][2];
+    let c22 = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+
+    let det = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+
+    [
+        [c00 / det, c10 / det, c20 / det],
+        [c01 / det, c11 / det, c21 / det],
+        [c02 / det, c12 / det, c22 / det],
+    ]
+}
+
+pub(crate) fn to_mat3(m: &M3) -> Mat3<f32> {
+    Mat3::new(
+        m[0][0] as f32, m[0][1] as f32, m[0][2] as f32,
+        m[1][0] as f32, m[1][1] as f32, m[1][2] as f32,
+        m[2][0] as f32, m[2][1] as f32, m[2][2] as f32
+    )
+}
+
+#[cfg(test)]
+pub(crate) fn assert_matrix_eq(a: &M3, b: &M3, epsilon: f64) {
+    for r in 0..3 {
+        for c in 0..3 {
+            assert!((a[r][c] - b[r][c]).abs() < epsilon,
+                    "[{}][{}]: {} != {}", r, c, a[r][c], b[r][c]);
+        }
+    }
+}
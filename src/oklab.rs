@@ -0,0 +1,240 @@
+use siege_math::{Vec3, Mat3};
+use crate::cie1931::{Cie1931, D65};
+use crate::rgb::RgbPrimaries;
+use crate::srgb::{LinearSrgb, SrgbPrimaries};
+use crate::matrix::{self, M3};
+
+// Matrices from https://bottosson.github.io/posts/oklab/
+
+// CIE 1931 XYZ (D65) to cone response
+const M1: M3 = [
+    [0.8189330101, 0.3618667424, -0.1288597137],
+    [0.0329845436, 0.9293118715, 0.0361456387],
+    [0.0482003018, 0.2643662691, 0.6338517070],
+];
+
+// Non-linear cone response to Lab
+const M2: M3 = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+const M1_INV: M3 = matrix::invert(&M1);
+const M2_INV: M3 = matrix::invert(&M2);
+
+const SRGB_TO_LMS: M3 = matrix::mul(&M1, &<SrgbPrimaries as RgbPrimaries>::RGB_TO_XYZ);
+const LMS_TO_SRGB: M3 = matrix::mul(&<SrgbPrimaries as RgbPrimaries>::XYZ_TO_RGB, &M1_INV);
+
+/// Oklab, a perceptual colorspace by Björn Ottosson (2020), with a D65 white.
+///
+/// L is 0.0 for black and 1.0 for the reference white. a and b are roughly
+/// within ±0.4 for real colors.
+#[derive(Debug, Clone, Copy)]
+pub struct Oklab {
+    pub v: Vec3<f32>
+}
+
+impl Oklab {
+    pub fn new(l: f32, a: f32, b: f32) -> Oklab {
+        Oklab {
+            v: Vec3::new(l, a, b)
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn a(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn b(&self) -> f32 {
+        self.v.z
+    }
+
+    // (linear) cone response to Oklab
+    #[inline]
+    fn from_lms(lms: Vec3<f32>) -> Oklab {
+        let lms_ = Vec3::new(lms.x.cbrt(), lms.y.cbrt(), lms.z.cbrt());
+        let m2: Mat3<f32> = matrix::to_mat3(&M2);
+        Oklab { v: &m2 * &lms_ }
+    }
+
+    // Oklab to (linear) cone response
+    #[inline]
+    fn to_lms(self) -> Vec3<f32> {
+        let m2_inv: Mat3<f32> = matrix::to_mat3(&M2_INV);
+        let lms_ = &m2_inv * &self.v;
+        Vec3::new(lms_.x * lms_.x * lms_.x,
+                  lms_.y * lms_.y * lms_.y,
+                  lms_.z * lms_.z * lms_.z)
+    }
+}
+
+// The direct paths, with the sRGB matrices folded into M1 and its inverse
+impl From<LinearSrgb> for Oklab {
+    fn from(input: LinearSrgb) -> Oklab {
+        let m: Mat3<f32> = matrix::to_mat3(&SRGB_TO_LMS);
+        Oklab::from_lms(&m * &input.v)
+    }
+}
+
+impl From<Oklab> for LinearSrgb {
+    fn from(input: Oklab) -> LinearSrgb {
+        let m: Mat3<f32> = matrix::to_mat3(&LMS_TO_SRGB);
+        let v = &m * &input.to_lms();
+        LinearSrgb::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Cie1931<D65>> for Oklab {
+    fn from(input: Cie1931<D65>) -> Oklab {
+        let m1: Mat3<f32> = matrix::to_mat3(&M1);
+        Oklab::from_lms(&m1 * &input.v)
+    }
+}
+
+impl From<Oklab> for Cie1931<D65> {
+    fn from(input: Oklab) -> Cie1931<D65> {
+        let m1_inv: Mat3<f32> = matrix::to_mat3(&M1_INV);
+        let v = &m1_inv * &input.to_lms();
+        Cie1931::new(v.x, v.y, v.z)
+    }
+}
+
+/// Oklch, the cylindrical form of Oklab. Hue is in degrees, from 0 up to 360.
+#[derive(Debug, Clone, Copy)]
+pub struct Oklch {
+    pub v: Vec3<f32>
+}
+
+impl Oklch {
+    pub fn new(l: f32, c: f32, h: f32) -> Oklch {
+        Oklch {
+            v: Vec3::new(l, c, h)
+        }
+    }
+
+    #[inline]
+    pub fn l(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn c(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn h(&self) -> f32 {
+        self.v.z
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(input: Oklab) -> Oklch {
+        let c = input.a().hypot(input.b());
+        let mut h = input.b().atan2(input.a()).to_degrees();
+        if h < 0.0 { h += 360.0; }
+        Oklch::new(input.l(), c, h)
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(input: Oklch) -> Oklab {
+        let (sin, cos) = input.h().to_radians().sin_cos();
+        Oklab::new(input.l(), input.c() * cos, input.c() * sin)
+    }
+}
+
+impl From<LinearSrgb> for Oklch {
+    fn from(input: LinearSrgb) -> Oklch {
+        let lab: Oklab = From::from(input);
+        From::from(lab)
+    }
+}
+
+impl From<Oklch> for LinearSrgb {
+    fn from(input: Oklch) -> LinearSrgb {
+        let lab: Oklab = From::from(input);
+        From::from(lab)
+    }
+}
+
+impl From<Cie1931<D65>> for Oklch {
+    fn from(input: Cie1931<D65>) -> Oklch {
+        let lab: Oklab = From::from(input);
+        From::from(lab)
+    }
+}
+
+impl From<Oklch> for Cie1931<D65> {
+    fn from(input: Oklch) -> Cie1931<D65> {
+        let lab: Oklab = From::from(input);
+        From::from(lab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_values() {
+        // Table from https://bottosson.github.io/posts/oklab/ (given to 3 places)
+        let table = [
+            ((0.950, 1.000, 1.089), (1.000, 0.000, 0.000)),
+            ((1.000, 0.000, 0.000), (0.450, 1.236, -0.019)),
+            ((0.000, 1.000, 0.000), (0.922, -0.671, 0.263)),
+            ((0.000, 0.000, 1.000), (0.153, -1.415, -0.449)),
+        ];
+
+        for &((x, y, z), (l, a, b)) in table.iter() {
+            let lab: Oklab = From::from(Cie1931::<D65>::new(x, y, z));
+            assert!((lab.l() - l).abs() < 0.0015);
+            assert!((lab.a() - a).abs() < 0.0015);
+            assert!((lab.b() - b).abs() < 0.0015);
+
+            let back: Cie1931<D65> = From::from(lab);
+            assert!((back.x() - x).abs() < 0.0001);
+            assert!((back.y() - y).abs() < 0.0001);
+            assert!((back.z() - z).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_srgb_paths_agree() {
+        let srgb = LinearSrgb::new(0.8, 0.3, 0.1);
+        let fast: Oklab = From::from(srgb);
+        let xyz: Cie1931<D65> = From::from(srgb);
+        let slow: Oklab = From::from(xyz);
+        assert!((fast.l() - slow.l()).abs() < 0.001);
+        assert!((fast.a() - slow.a()).abs() < 0.001);
+        assert!((fast.b() - slow.b()).abs() < 0.001);
+
+        // M1 only takes the D65 white to equal cone responses to about 1e-4
+        let white: Oklab = From::from(LinearSrgb::new(1.0, 1.0, 1.0));
+        assert!((white.l() - 1.0).abs() < 0.0001);
+        assert!(white.a().abs() < 0.0002);
+        assert!(white.b().abs() < 0.0002);
+
+        // and back, through either path
+        let lab = Oklab::new(0.6, 0.1, -0.05);
+        let fast: LinearSrgb = From::from(lab);
+        let slow: LinearSrgb = From::from(Cie1931::<D65>::from(lab));
+        assert!((fast.r() - slow.r()).abs() < 0.00001);
+        assert!((fast.g() - slow.g()).abs() < 0.00001);
+        assert!((fast.b() - slow.b()).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_to_and_from_srgb() {
+        let srgb = LinearSrgb::new(0.2, 0.6, 0.9);
+        let lch: Oklch = From::from(srgb);
+        let back: LinearSrgb = From::from(lch);
+        assert!((srgb.r() - back.r()).abs() < 0.00001);
+        assert!((srgb.g() - back.g()).abs() < 0.00001);
+        assert!((srgb.b() - back.b()).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,169 @@
+use siege_math::Vec2;
+use crate::cie1931::{D65, Dci};
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
+use crate::srgb::{LinearSrgb, SrgbTransfer};
+use super::Chromaticity;
+
+pub const DISPLAY_P3_CHROMATICITIES: Chromaticity = Chromaticity {
+    red:   Vec2 { x: 0.680, y: 0.320 },
+    green: Vec2 { x: 0.265, y: 0.690 },
+    blue:  Vec2 { x: 0.150, y: 0.060 },
+    white: Vec2 { x: 0.3127, y: 0.3290 }
+};
+
+pub const DCI_P3_CHROMATICITIES: Chromaticity = Chromaticity {
+    red:   Vec2 { x: 0.680, y: 0.320 },
+    green: Vec2 { x: 0.265, y: 0.690 },
+    blue:  Vec2 { x: 0.150, y: 0.060 },
+    white: Vec2 { x: 0.314, y: 0.351 }
+};
+
+/// The P3 primaries, with the D65 white point (Display P3, P3-D65)
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayP3Primaries;
+
+impl RgbPrimaries for DisplayP3Primaries {
+    type White = D65;
+    const CHROMATICITY: Chromaticity = DISPLAY_P3_CHROMATICITIES;
+}
+
+/// The P3 primaries, with the DCI projector white point (SMPTE RP 431-2)
+#[derive(Debug, Clone, Copy)]
+pub struct DciP3Primaries;
+
+impl RgbPrimaries for DciP3Primaries {
+    type White = Dci;
+    const CHROMATICITY: Chromaticity = DCI_P3_CHROMATICITIES;
+}
+
+/// The DCI transfer function, a pure 2.6 gamma
+#[derive(Debug, Clone, Copy)]
+pub struct Gamma26Transfer;
+
+impl TransferFunction for Gamma26Transfer {
+    fn encode(x: f32) -> f32 {
+        x.max(0.0).powf(1.0 / 2.6)
+    }
+
+    fn decode(x: f32) -> f32 {
+        x.max(0.0).powf(2.6)
+    }
+}
+
+/// Display P3 (Apple), the P3 primaries with a D65 white and the sRGB
+/// transfer function
+///   Red is (0.680, 0.320)
+///   Green is (0.265, 0.690)
+///   Blue is (0.150, 0.060)
+///   White point is CIE Standard D65 (0.3127, 0.3290)
+pub type DisplayP3 = Rgb<DisplayP3Primaries, SrgbTransfer>;
+
+/// Display P3 with linear transfer characteristics
+pub type LinearDisplayP3 = Rgb<DisplayP3Primaries, Linear>;
+
+/// DCI-P3, for digital cinema projection: the P3 primaries with the DCI
+/// white (0.314, 0.351) and a 2.6 gamma. Conversions to and from
+/// `Cie1931<Dci>` are provided generically for `Rgb`.
+pub type DciP3 = Rgb<DciP3Primaries, Gamma26Transfer>;
+
+/// DCI-P3 with linear transfer characteristics
+pub type LinearDciP3 = Rgb<DciP3Primaries, Linear>;
+
+impl From<LinearSrgb> for LinearDisplayP3 {
+    fn from(input: LinearSrgb) -> LinearDisplayP3 {
+        input.convert()
+    }
+}
+
+impl From<LinearDisplayP3> for LinearSrgb {
+    fn from(input: LinearDisplayP3) -> LinearSrgb {
+        input.convert()
+    }
+}
+
+impl From<DisplayP3> for LinearDisplayP3 {
+    fn from(input: DisplayP3) -> LinearDisplayP3 {
+        input.to_linear()
+    }
+}
+
+impl From<LinearDisplayP3> for DisplayP3 {
+    fn from(input: LinearDisplayP3) -> DisplayP3 {
+        DisplayP3::from_linear(input)
+    }
+}
+
+// DCI-P3 and sRGB have different white points, so these adapt (Bradford)
+impl From<LinearSrgb> for LinearDciP3 {
+    fn from(input: LinearSrgb) -> LinearDciP3 {
+        input.convert()
+    }
+}
+
+impl From<LinearDciP3> for LinearSrgb {
+    fn from(input: LinearDciP3) -> LinearSrgb {
+        input.convert()
+    }
+}
+
+impl From<DciP3> for LinearDciP3 {
+    fn from(input: DciP3) -> LinearDciP3 {
+        input.to_linear()
+    }
+}
+
+impl From<LinearDciP3> for DciP3 {
+    fn from(input: LinearDciP3) -> DciP3 {
+        DciP3::from_linear(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::Cie1931;
+    use crate::matrix::assert_matrix_eq;
+
+    #[test]
+    fn test_matrices() {
+        assert_matrix_eq(
+            &DisplayP3Primaries::RGB_TO_XYZ,
+            &[[0.4865709, 0.2656677, 0.1982173],
+              [0.2289746, 0.6917385, 0.0792869],
+              [0.0000000, 0.0451134, 1.0439444]],
+            0.00001);
+
+        assert_matrix_eq(
+            &DciP3Primaries::RGB_TO_XYZ,
+            &[[0.4451698, 0.2771344, 0.1722827],
+              [0.2094917, 0.7215953, 0.0689131],
+              [0.0000000, 0.0470606, 0.9073554]],
+            0.00001);
+    }
+
+    #[test]
+    fn test_conversions() {
+        // sRGB is inside P3
+        let red: LinearDisplayP3 = From::from(LinearSrgb::new(1.0, 0.0, 0.0));
+        assert!((red.r() - 0.8225).abs() < 0.0005);
+        assert!((red.g() - 0.0332).abs() < 0.0005);
+        assert!((red.b() - 0.0171).abs() < 0.0005);
+
+        // white adapts to the DCI white
+        let white: LinearDciP3 = From::from(LinearSrgb::new(1.0, 1.0, 1.0));
+        assert!((white.r() - 1.0).abs() < 0.0001);
+        assert!((white.g() - 1.0).abs() < 0.0001);
+        assert!((white.b() - 1.0).abs() < 0.0001);
+        let xyz: Cie1931<Dci> = From::from(white);
+        assert!((xyz.x() / (xyz.x() + xyz.y() + xyz.z()) - 0.314).abs() < 0.0001);
+
+        let a = DciP3::new(0.2, 0.5, 0.8);
+        let b: DciP3 = From::from(LinearDciP3::from(a));
+        assert!((a.g() - b.g()).abs() < 0.00001);
+        assert!((Gamma26Transfer::decode(0.5) - 0.16494).abs() < 0.00001);
+
+        let a = DisplayP3::new(0.2, 0.5, 0.8);
+        let b: DisplayP3 = From::from(LinearDisplayP3::from(a));
+        assert!((a.b() - b.b()).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,197 @@
+// Photometric quantities, and their connection to color.
+//
+// Photometric values are radiometric ones weighted by the photopic luminous
+// efficiency function V(λ), which is the CIE 1931 ȳ color matching function,
+// and scaled by the maximum luminous efficacy of 683 lm/W.
+
+use std::f32::consts::PI;
+use crate::cie1931::{Cie1931, Cie1931xyY, D65, E};
+use crate::cmf::{CIE1931_2DEG, CMF_START, CMF_STEP};
+use crate::colortemp::ColorTemp;
+use crate::spd::{Spd, MAX_LUMINOUS_EFFICACY};
+use crate::srgb::LinearSrgb;
+
+/// The Stefan-Boltzmann constant, σ, in W/(m²·K⁴)
+pub const STEFAN_BOLTZMANN: f64 = 5.670_374_419e-8;
+
+macro_rules! photometric {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(pub f32);
+
+        impl $name {
+            pub fn new(value: f32) -> $name {
+                $name(value)
+            }
+        }
+    };
+}
+
+photometric!(
+    /// Luminance in cd/m² (nits): the brightness of a surface or light as seen
+    Luminance);
+photometric!(
+    /// Illuminance in lux (lm/m²): the light falling on a surface
+    Illuminance);
+photometric!(
+    /// Luminous flux in lumens: the total light emitted by a source
+    LuminousFlux);
+photometric!(
+    /// Luminous intensity in candela (lm/sr): the light emitted in a direction
+    LuminousIntensity);
+
+impl Luminance {
+    /// A linear sRGB color of a blackbody at this temperature with this
+    /// luminance, so that its values are in cd/m²
+    pub fn to_linear_srgb(&self, temperature: ColorTemp) -> LinearSrgb {
+        blackbody_linear_srgb(temperature, self.0)
+    }
+}
+
+impl LuminousFlux {
+    /// The intensity of an isotropic point light with this flux
+    pub fn to_isotropic_intensity(&self) -> LuminousIntensity {
+        LuminousIntensity(self.0 / (4.0 * PI))
+    }
+
+    /// The intensity of a spot light with this flux spread evenly over a
+    /// cone with the given half angle (in radians)
+    pub fn to_spot_intensity(&self, half_angle: f32) -> LuminousIntensity {
+        LuminousIntensity(self.0 / (2.0 * PI * (1.0 - half_angle.cos())))
+    }
+
+    /// The luminance of a one-sided Lambertian emitter of the given area in m²
+    pub fn to_area_luminance(&self, area: f32) -> Luminance {
+        Luminance(self.0 / (PI * area))
+    }
+}
+
+impl LuminousIntensity {
+    /// The illuminance this light gives on a surface facing it at a
+    /// distance in m, by the inverse square law
+    pub fn illuminance_at(&self, distance: f32) -> Illuminance {
+        Illuminance(self.0 / (distance * distance))
+    }
+}
+
+impl Illuminance {
+    /// The luminance of a Lambertian surface of the given reflectance, lit by
+    /// this illuminance
+    pub fn reflected_luminance(&self, reflectance: f32) -> Luminance {
+        Luminance(self.0 * reflectance / PI)
+    }
+}
+
+/// The photopic luminous efficiency function V(λ), at a wavelength in nm
+pub fn luminous_efficiency(wavelength: f32) -> f32 {
+    let t = (wavelength - CMF_START) / CMF_STEP;
+    let last = CIE1931_2DEG.len() - 1;
+    if t < 0.0 || t > last as f32 {
+        return 0.0;
+    }
+    let i = (t as usize).min(last - 1);
+    let f = t - i as f32;
+    CIE1931_2DEG[i][1] * (1.0 - f) + CIE1931_2DEG[i + 1][1] * f
+}
+
+/// A linear sRGB color with the chromaticity of a blackbody at the given
+/// temperature, and the given luminance. It is not adapted: a blackbody at
+/// 6504 K is close to white, lower temperatures are orange.
+pub fn blackbody_linear_srgb(temperature: ColorTemp, luminance: f32) -> LinearSrgb {
+    let xy = temperature.to_cie1931xyY_exact();
+    let xyz: Cie1931<D65> = From::from(Cie1931xyY::new(xy.x(), xy.y(), luminance));
+    From::from(xyz)
+}
+
+impl ColorTemp {
+    /// The luminous efficacy of radiation of a blackbody at this
+    /// temperature, in lm/W: its luminance over its total radiance, σT⁴/π
+    pub fn luminous_efficacy(&self) -> f32 {
+        let luminance = Spd::blackbody(*self).emissive_cie1931::<E>().y() as f64;
+        let radiance = STEFAN_BOLTZMANN * (self.0 as f64).powi(4) / std::f64::consts::PI;
+        (luminance / radiance) as f32
+    }
+}
+
+impl Spd {
+    /// The luminous efficacy of radiation of an emissive spectrum in lm/W,
+    /// relative to its power over the sampled range
+    pub fn luminous_efficacy(&self) -> f32 {
+        let (w, v) = (self.wavelengths(), self.values());
+        let (mut luminous, mut radiant) = (0.0_f64, 0.0_f64);
+        for i in 1..w.len() {
+            // trapezoidal rule
+            let dw = (w[i] - w[i - 1]) as f64 / 2.0;
+            luminous += dw * (v[i - 1] * luminous_efficiency(w[i - 1])
+                              + v[i] * luminous_efficiency(w[i])) as f64;
+            radiant += dw * (v[i - 1] + v[i]) as f64;
+        }
+        (MAX_LUMINOUS_EFFICACY as f64 * luminous / radiant) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_efficiency() {
+        assert!((luminous_efficiency(555.0) - 1.0).abs() < 0.001);
+        assert!((luminous_efficiency(557.5) - 0.9975).abs() < 0.001);
+        assert_eq!(luminous_efficiency(300.0), 0.0);
+
+        // the ends of the table are included
+        assert_eq!(luminous_efficiency(380.0), CIE1931_2DEG[0][1]);
+        assert_eq!(luminous_efficiency(780.0), CIE1931_2DEG[80][1]);
+        assert_eq!(luminous_efficiency(780.1), 0.0);
+
+        // monochromatic 555 nm light is 683 lm/W
+        let green = Spd::new(vec![554.0, 555.0, 556.0], vec![0.0, 1.0, 0.0]);
+        assert!((green.luminous_efficacy() - 683.0).abs() < 1.0);
+        // equal energy over the visible range
+        assert!((Spd::constant(1.0).luminous_efficacy() - 182.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_blackbody_efficacy() {
+        // about 54 lm/W at 4000 K and 95 lm/W at 7000 K, with a peak near
+        // 6600 K
+        assert!((ColorTemp::new(4000.0).luminous_efficacy() - 54.0).abs() < 1.0);
+        assert!((ColorTemp::new(7000.0).luminous_efficacy() - 95.0).abs() < 1.0);
+        let peak = ColorTemp::new(6600.0).luminous_efficacy();
+        assert!(ColorTemp::new(5000.0).luminous_efficacy() < peak);
+        assert!(ColorTemp::new(9000.0).luminous_efficacy() < peak);
+    }
+
+    #[test]
+    fn test_lights() {
+        // an 800 lm incandescent bulb
+        let intensity = LuminousFlux::new(800.0).to_isotropic_intensity();
+        assert!((intensity.0 - 63.662).abs() < 0.001);
+        let lux = intensity.illuminance_at(2.0);
+        assert!((lux.0 - 15.915).abs() < 0.001);
+        let white_paper = lux.reflected_luminance(0.8);
+        assert!((white_paper.0 - 4.053).abs() < 0.001);
+
+        // a hemisphere is 2π sr
+        let spot = LuminousFlux::new(800.0).to_spot_intensity(PI / 2.0);
+        assert!((spot.0 - 2.0 * intensity.0).abs() < 0.001);
+
+        // the paper it lights has the right luminance, and is warm
+        let rgb = white_paper.to_linear_srgb(ColorTemp::new(2700.0));
+        assert!((rgb.get_luminance() - 4.053).abs() < 0.001);
+        assert!(rgb.r() > rgb.g() && rgb.g() > rgb.b());
+
+        // as does an 800 lm panel of 0.25 m²
+        let panel = LuminousFlux::new(800.0).to_area_luminance(0.25);
+        assert!((panel.0 - 1018.59).abs() < 0.01);
+        let rgb = panel.to_linear_srgb(ColorTemp::new(4000.0));
+        assert!((rgb.get_luminance() - 1018.59).abs() < 0.1);
+
+        // a blackbody near D65 is near white
+        let rgb = Luminance::new(1.0).to_linear_srgb(ColorTemp::new(6504.0));
+        assert!((rgb.r() - 1.0).abs() < 0.05);
+        assert!((rgb.b() - 1.0).abs() < 0.05);
+    }
+}
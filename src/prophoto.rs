@@ -0,0 +1,118 @@
+use siege_math::Vec2;
+use crate::cie1931::D50;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
+use crate::srgb::LinearSrgb;
+use super::Chromaticity;
+
+pub const PROPHOTO_RGB_CHROMATICITIES: Chromaticity = Chromaticity {
+    red:   Vec2 { x: 0.7347, y: 0.2653 },
+    green: Vec2 { x: 0.1596, y: 0.8404 },
+    blue:  Vec2 { x: 0.0366, y: 0.0001 },
+    white: Vec2 { x: 0.3457, y: 0.3585 }
+};
+
+/// The ProPhoto (ROMM) RGB primaries, with the D50 white point
+#[derive(Debug, Clone, Copy)]
+pub struct ProPhotoRgbPrimaries;
+
+impl RgbPrimaries for ProPhotoRgbPrimaries {
+    type White = D50;
+    const CHROMATICITY: Chromaticity = PROPHOTO_RGB_CHROMATICITIES;
+}
+
+/// The ROMM RGB transfer function (ISO 22028-2): a 1.8 gamma with a linear
+/// segment of slope 16 below 1/512
+#[derive(Debug, Clone, Copy)]
+pub struct ProPhotoTransfer;
+
+const ET: f32 = 1.0 / 512.0;
+
+impl TransferFunction for ProPhotoTransfer {
+    fn encode(x: f32) -> f32 {
+        if x < ET { 16.0 * x.max(0.0) }
+        else { x.powf(1.0 / 1.8) }
+    }
+
+    fn decode(x: f32) -> f32 {
+        if x < 16.0 * ET { x.max(0.0) / 16.0 }
+        else { x.powf(1.8) }
+    }
+}
+
+/// ProPhoto RGB (ROMM RGB), gamma encoded
+///   Red is (0.7347, 0.2653)
+///   Green is (0.1596, 0.8404)
+///   Blue is (0.0366, 0.0001)
+///   White point is CIE Standard D50 (0.3457, 0.3585)
+/// Some of the gamut is outside of the spectral locus. Conversions to and
+/// from `Cie1931<D50>` are provided generically for `Rgb`.
+pub type ProPhotoRgb = Rgb<ProPhotoRgbPrimaries, ProPhotoTransfer>;
+
+/// ProPhoto RGB with linear transfer characteristics
+pub type LinearProPhotoRgb = Rgb<ProPhotoRgbPrimaries, Linear>;
+
+// These adapt between D50 and D65 (Bradford)
+impl From<LinearSrgb> for LinearProPhotoRgb {
+    fn from(input: LinearSrgb) -> LinearProPhotoRgb {
+        input.convert()
+    }
+}
+
+impl From<LinearProPhotoRgb> for LinearSrgb {
+    fn from(input: LinearProPhotoRgb) -> LinearSrgb {
+        input.convert()
+    }
+}
+
+impl From<ProPhotoRgb> for LinearProPhotoRgb {
+    fn from(input: ProPhotoRgb) -> LinearProPhotoRgb {
+        input.to_linear()
+    }
+}
+
+impl From<LinearProPhotoRgb> for ProPhotoRgb {
+    fn from(input: LinearProPhotoRgb) -> ProPhotoRgb {
+        ProPhotoRgb::from_linear(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::assert_matrix_eq;
+
+    #[test]
+    fn test_matrix() {
+        // Lindbloom's matrix uses a slightly different D50 white (0.34567, 0.35850)
+        assert_matrix_eq(
+            &ProPhotoRgbPrimaries::RGB_TO_XYZ,
+            &[[0.7976749, 0.1351917, 0.0313534],
+              [0.2880402, 0.7118741, 0.0000857],
+              [0.0000000, 0.0000000, 0.8252100]],
+            0.0002);
+    }
+
+    #[test]
+    fn test_conversions() {
+        // white adapts to white, and sRGB is well inside
+        let white: LinearProPhotoRgb = From::from(LinearSrgb::new(1.0, 1.0, 1.0));
+        assert!((white.r() - 1.0).abs() < 0.0001);
+        assert!((white.g() - 1.0).abs() < 0.0001);
+        assert!((white.b() - 1.0).abs() < 0.0001);
+        let red: LinearProPhotoRgb = From::from(LinearSrgb::new(1.0, 0.0, 0.0));
+        assert!((red.r() - 0.5294).abs() < 0.0005);
+        assert!((red.g() - 0.0984).abs() < 0.0005);
+        assert!((red.b() - 0.0169).abs() < 0.0005);
+        let back: LinearSrgb = From::from(red);
+        assert!((back.r() - 1.0).abs() < 0.0001);
+        assert!(back.g().abs() < 0.0001);
+
+        // the linear toe meets the gamma curve
+        assert!((ProPhotoTransfer::encode(ET) - 1.0 / 32.0).abs() < 0.000001);
+        assert!((ProPhotoTransfer::decode(1.0 / 32.0) - ET).abs() < 0.000001);
+        let a = ProPhotoRgb::new(0.01, 0.5, 0.8);
+        let b: ProPhotoRgb = From::from(LinearProPhotoRgb::from(a));
+        assert!((a.r() - b.r()).abs() < 0.00001);
+        assert!((a.g() - b.g()).abs() < 0.00001);
+    }
+}
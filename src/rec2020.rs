@@ -1,10 +1,155 @@
-
 use siege_math::Vec2;
+use crate::cie1931::D65;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
+use crate::srgb::LinearSrgb;
 use super::Chromaticity;
 
 pub const REC2020_CHROMATICITIES: Chromaticity = Chromaticity {
     red:   Vec2 { x: 0.708, y: 0.292 },
     green: Vec2 { x: 0.170, y: 0.797 },
     blue:  Vec2 { x: 0.131, y: 0.046 },
-    white: Vec2 { x: 0.3217, y: 0.3290 }
+    white: Vec2 { x: 0.3127, y: 0.3290 }
 };
+
+/// The Rec.2020 primaries, with the D65 white point
+#[derive(Debug, Clone, Copy)]
+pub struct Rec2020Primaries;
+
+impl RgbPrimaries for Rec2020Primaries {
+    type White = D65;
+    const CHROMATICITY: Chromaticity = REC2020_CHROMATICITIES;
+}
+
+/// The BT.2020 OETF (which is the same curve as BT.709)
+#[derive(Debug, Clone, Copy)]
+pub struct Bt2020Transfer;
+
+// BT.2020 OETF constants (the 12-bit precision values, which also serve 10-bit)
+const ALPHA: f32 = 1.099_296_8;
+const BETA: f32 = 0.018_053_97;
+
+impl TransferFunction for Bt2020Transfer {
+    fn encode(x: f32) -> f32 {
+        if x < BETA { 4.5 * x }
+        else { ALPHA * x.powf(0.45) - (ALPHA - 1.0) }
+    }
+
+    fn decode(x: f32) -> f32 {
+        if x < 4.5 * BETA { x / 4.5 }
+        else { ((x + ALPHA - 1.0) / ALPHA).powf(1.0/0.45) }
+    }
+}
+
+/// ITU-R BT.2020 (Rec.2020), gamma encoded with the BT.2020 OETF
+pub type Rec2020 = Rgb<Rec2020Primaries, Bt2020Transfer>;
+
+/// ITU-R BT.2020 (Rec.2020) with linear transfer characteristics
+///   Red is (0.708, 0.292)
+///   Green is (0.170, 0.797)
+///   Blue is (0.131, 0.046)
+///   White point is CIE Standard D65 (0.3127, 0.3290)
+/// Values can go outside of the [0,1] range (e.g. for HDR).
+/// Conversions to and from `Cie1931<D65>` are provided generically for `Rgb`.
+pub type LinearRec2020 = Rgb<Rec2020Primaries, Linear>;
+
+// Rec.2020 and sRGB share the D65 white point, so no chromatic adaptation
+// is required and we can go straight through CIE 1931 XYZ.
+impl From<LinearSrgb> for LinearRec2020 {
+    fn from(input: LinearSrgb) -> LinearRec2020 {
+        input.convert()
+    }
+}
+
+impl From<LinearRec2020> for LinearSrgb {
+    fn from(input: LinearRec2020) -> LinearSrgb {
+        input.convert()
+    }
+}
+
+impl From<Rec2020> for LinearRec2020 {
+    fn from(input: Rec2020) -> LinearRec2020 {
+        input.to_linear()
+    }
+}
+
+impl From<LinearRec2020> for Rec2020 {
+    fn from(input: LinearRec2020) -> Rec2020 {
+        Rec2020::from_linear(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::Cie1931;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn test_white_point() {
+        // Rec.2020 uses D65, the same white point as sRGB
+        assert_eq!(REC2020_CHROMATICITIES.white.x, 0.3127);
+        assert_eq!(REC2020_CHROMATICITIES.white.y, 0.3290);
+
+        // Equal RGB should land on the white point
+        let xyz: Cie1931<D65> = From::from(LinearRec2020::new(1.0, 1.0, 1.0));
+        assert!((xyz.x() - 0.95047).abs() < 0.0001);
+        assert!((xyz.y() - 1.0).abs() < 0.0001);
+        assert!((xyz.z() - 1.08906).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_to_and_from_cie1931() {
+        let a = LinearRec2020::new(0.5, 0.2, 0.7);
+        let xyz: Cie1931<D65> = From::from(a);
+        let b: LinearRec2020 = From::from(xyz);
+
+        assert!(
+            a.v.approx_eq(
+                &b.v,
+                (10.0 * f32::EPSILON, 10)
+            )
+        );
+    }
+
+    #[test]
+    fn test_srgb_to_rec2020() {
+        // Reference values from ITU-R BT.2087 (the BT.709 to BT.2020 matrix)
+        let red: LinearRec2020 = From::from(LinearSrgb::new(1.0, 0.0, 0.0));
+        assert!((red.r() - 0.6274).abs() < 0.001);
+        assert!((red.g() - 0.0691).abs() < 0.001);
+        assert!((red.b() - 0.0164).abs() < 0.001);
+
+        let green: LinearRec2020 = From::from(LinearSrgb::new(0.0, 1.0, 0.0));
+        assert!((green.r() - 0.3293).abs() < 0.001);
+        assert!((green.g() - 0.9195).abs() < 0.001);
+        assert!((green.b() - 0.0880).abs() < 0.001);
+
+        let blue: LinearRec2020 = From::from(LinearSrgb::new(0.0, 0.0, 1.0));
+        assert!((blue.r() - 0.0433).abs() < 0.001);
+        assert!((blue.g() - 0.0114).abs() < 0.001);
+        assert!((blue.b() - 0.8956).abs() < 0.001);
+
+        let back: LinearSrgb = From::from(blue);
+        assert!(back.r().abs() < 0.0001);
+        assert!(back.g().abs() < 0.0001);
+        assert!((back.b() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_to_and_from_linear() {
+        let a = Rec2020::new(0.0124, 0.5, 0.9812);
+        let l: LinearRec2020 = From::from(a);
+        let b: Rec2020 = From::from(l);
+
+        assert!(
+            a.v.approx_eq(
+                &b.v,
+                (10.0 * f32::EPSILON, 10)
+            )
+        );
+
+        // 18% grey encodes to about 0.409 under the BT.2020 OETF
+        let grey: Rec2020 = From::from(LinearRec2020::new(0.18, 0.18, 0.18));
+        assert!((grey.r() - 0.4090).abs() < 0.0005);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::rec2020::Bt2020Transfer;
+use crate::rgb::{Rgb, TransferFunction};
+use crate::srgb::{LinearSrgb, SrgbPrimaries};
+
+/// The BT.709 OETF. This is the same curve as BT.2020's, whose more precise
+/// constants are used.
+pub type Bt709Transfer = Bt2020Transfer;
+
+/// The BT.1886 EOTF of a reference display with a zero black level, which is
+/// a pure 2.4 gamma
+#[derive(Debug, Clone, Copy)]
+pub struct Bt1886Transfer;
+
+impl TransferFunction for Bt1886Transfer {
+    fn encode(x: f32) -> f32 {
+        x.max(0.0).powf(1.0 / 2.4)
+    }
+
+    fn decode(x: f32) -> f32 {
+        x.max(0.0).powf(2.4)
+    }
+}
+
+/// ITU-R BT.709 (Rec.709) as a camera encodes it, with the BT.709 OETF.
+/// The primaries and white point are the same as sRGB's, so the linear form
+/// is `LinearSrgb`.
+pub type Rec709 = Rgb<SrgbPrimaries, Bt709Transfer>;
+
+/// Rec.709 as a display decodes it, with the BT.1886 EOTF. Use this to
+/// encode display-referred (e.g. tone mapped) values.
+pub type Rec709Display = Rgb<SrgbPrimaries, Bt1886Transfer>;
+
+impl From<Rec709> for LinearSrgb {
+    fn from(input: Rec709) -> LinearSrgb {
+        input.to_linear()
+    }
+}
+
+impl From<LinearSrgb> for Rec709 {
+    fn from(input: LinearSrgb) -> Rec709 {
+        Rec709::from_linear(input)
+    }
+}
+
+impl From<Rec709Display> for LinearSrgb {
+    fn from(input: Rec709Display) -> LinearSrgb {
+        input.to_linear()
+    }
+}
+
+impl From<LinearSrgb> for Rec709Display {
+    fn from(input: LinearSrgb) -> Rec709Display {
+        Rec709Display::from_linear(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfers() {
+        // 18% grey is about 0.409 through the OETF, 0.490 through BT.1886
+        let grey: Rec709 = From::from(LinearSrgb::new(0.18, 0.18, 0.18));
+        assert!((grey.r() - 0.4090).abs() < 0.0005);
+        let grey: Rec709Display = From::from(LinearSrgb::new(0.18, 0.18, 0.18));
+        assert!((grey.r() - 0.4898).abs() < 0.0005);
+
+        let a = Rec709::new(0.01, 0.5, 0.9);
+        let b: Rec709 = From::from(LinearSrgb::from(a));
+        assert!((a.r() - b.r()).abs() < 0.00001);
+        assert!((a.b() - b.b()).abs() < 0.00001);
+    }
+}
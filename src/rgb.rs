@@ -0,0 +1,184 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use siege_math::{Vec3, Mat3};
+use crate::cie1931::{Cie1931, Illuminant};
+use crate::chromaticities::Chromaticity;
+use crate::matrix;
+
+/// The primaries and white point of an RGB colorspace.
+///
+/// Implement this on a marker type to declare a new RGB colorspace. The
+/// conversion matrices are derived from the chromaticities at compile time.
+pub trait RgbPrimaries: Debug + Clone + Copy {
+    /// The white point of the colorspace. This must agree with
+    /// `CHROMATICITY.white`.
+    type White: Illuminant;
+
+    const CHROMATICITY: Chromaticity;
+
+    /// Linear RGB to CIE 1931 XYZ (at `White`)
+    const RGB_TO_XYZ: [[f64; 3]; 3] = Self::CHROMATICITY.rgb_to_xyz();
+
+    /// CIE 1931 XYZ (at `White`) to linear RGB
+    const XYZ_TO_RGB: [[f64; 3]; 3] = Self::CHROMATICITY.xyz_to_rgb();
+}
+
+/// The transfer characteristics (e.g. gamma) of an RGB encoding.
+pub trait TransferFunction: Debug + Clone + Copy {
+    /// Encode a linear value
+    fn encode(linear: f32) -> f32;
+
+    /// Decode an encoded value back to linear
+    fn decode(encoded: f32) -> f32;
+}
+
+/// Photometrically linear transfer characteristics
+#[derive(Debug, Clone, Copy)]
+pub struct Linear;
+
+impl TransferFunction for Linear {
+    #[inline]
+    fn encode(linear: f32) -> f32 {
+        linear
+    }
+
+    #[inline]
+    fn decode(encoded: f32) -> f32 {
+        encoded
+    }
+}
+
+/// An RGB color in the colorspace with primaries `S` encoded with the
+/// transfer function `T`.
+///
+/// Conversions to and from `Cie1931<S::White>` are provided for every space.
+/// Use `convert()` to go between any two RGB spaces.
+#[derive(Debug, Clone, Copy)]
+pub struct Rgb<S: RgbPrimaries, T: TransferFunction> {
+    pub v: Vec3<f32>,
+    _phantom: PhantomData<(S, T)>
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> Rgb<S, T> {
+    pub fn new(r: f32, g: f32, b: f32) -> Rgb<S, T> {
+        Rgb {
+            v: Vec3::new(r, g, b),
+            _phantom: PhantomData
+        }
+    }
+
+    #[inline]
+    pub fn r(&self) -> f32 {
+        self.v.x
+    }
+    #[inline]
+    pub fn g(&self) -> f32 {
+        self.v.y
+    }
+    #[inline]
+    pub fn b(&self) -> f32 {
+        self.v.z
+    }
+
+    /// Decode into the linear form of this space
+    pub fn to_linear(&self) -> Rgb<S, Linear> {
+        Rgb::new(T::decode(self.r()), T::decode(self.g()), T::decode(self.b()))
+    }
+
+    /// Encode from the linear form of this space
+    pub fn from_linear(linear: Rgb<S, Linear>) -> Rgb<S, T> {
+        Rgb::new(T::encode(linear.r()), T::encode(linear.g()), T::encode(linear.b()))
+    }
+
+    /// Convert to another RGB space (or another encoding of this one) by way
+    /// of CIE 1931 XYZ, adapting the white point if the spaces differ.
+    pub fn convert<S2, T2>(self) -> Rgb<S2, T2>
+    where S2: RgbPrimaries,
+          T2: TransferFunction,
+          Cie1931<S2::White>: From<Cie1931<S::White>>
+    {
+        let xyz: Cie1931<S::White> = From::from(self);
+        From::from(Cie1931::<S2::White>::from(xyz))
+    }
+}
+
+impl<S: RgbPrimaries> Rgb<S, Linear> {
+    pub fn get_luminance(&self) -> f32
+    {
+        // middle row of S::RGB_TO_XYZ
+        Vec3::<f32>::new(S::RGB_TO_XYZ[1][0] as f32,
+                         S::RGB_TO_XYZ[1][1] as f32,
+                         S::RGB_TO_XYZ[1][2] as f32).dot(self.v)
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Cie1931<S::White>> for Rgb<S, T> {
+    fn from(input: Cie1931<S::White>) -> Rgb<S, T> {
+        let m: Mat3<f32> = matrix::to_mat3(&S::XYZ_TO_RGB);
+        let v = &m * &input.v;
+        Rgb::from_linear(Rgb::new(v.x, v.y, v.z))
+    }
+}
+
+impl<S: RgbPrimaries, T: TransferFunction> From<Rgb<S, T>> for Cie1931<S::White> {
+    fn from(input: Rgb<S, T>) -> Cie1931<S::White> {
+        let m: Mat3<f32> = matrix::to_mat3(&S::RGB_TO_XYZ);
+        let v = &m * &input.to_linear().v;
+        Cie1931::new(v.x, v.y, v.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use siege_math::Vec2;
+    use crate::cie1931::{D50, D65};
+    use crate::srgb::{LinearSrgb, Srgb};
+    use crate::rec2020::LinearRec2020;
+
+    // A space declared purely from its markers
+    #[derive(Debug, Clone, Copy)]
+    struct WideGamut;
+    impl RgbPrimaries for WideGamut {
+        type White = D50;
+        const CHROMATICITY: Chromaticity = Chromaticity {
+            red:   Vec2 { x: 0.7347, y: 0.2653 },
+            green: Vec2 { x: 0.1152, y: 0.8264 },
+            blue:  Vec2 { x: 0.1566, y: 0.0177 },
+            white: Vec2 { x: 0.3457, y: 0.3585 }
+        };
+    }
+
+    #[test]
+    fn test_convert_same_white() {
+        let a = Srgb::new(0.2, 0.5, 0.9);
+        let b: LinearRec2020 = a.convert();
+        let c: Srgb = b.convert();
+        assert!((a.r() - c.r()).abs() < 0.00001);
+        assert!((a.g() - c.g()).abs() < 0.00001);
+        assert!((a.b() - c.b()).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_convert_adapting_white() {
+        // white must stay white when adapting D65 -> D50
+        let white: Rgb<WideGamut, Linear> = LinearSrgb::new(1.0, 1.0, 1.0).convert();
+        assert!((white.r() - 1.0).abs() < 0.001);
+        assert!((white.g() - 1.0).abs() < 0.001);
+        assert!((white.b() - 1.0).abs() < 0.001);
+
+        let a = LinearSrgb::new(0.1, 0.4, 0.3);
+        let b: Rgb<WideGamut, Linear> = a.convert();
+        let c: LinearSrgb = b.convert();
+        assert!((a.r() - c.r()).abs() < 0.00001);
+        assert!((a.g() - c.g()).abs() < 0.00001);
+        assert!((a.b() - c.b()).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_luminance() {
+        let xyz: Cie1931<D65> = From::from(LinearSrgb::new(0.3, 0.6, 0.9));
+        let l = LinearSrgb::new(0.3, 0.6, 0.9).get_luminance();
+        assert!((xyz.y() - l).abs() < 0.000001);
+    }
+}
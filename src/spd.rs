@@ -0,0 +1,231 @@
+use std::ops::{Add, Mul};
+use crate::cie1931::{Cie1931, Illuminant};
+use crate::cmf::{CIE1931_2DEG, CMF_STEP, cmf_wavelength};
+use crate::colortemp::ColorTemp;
+use crate::daylight::{Daylight, DAYLIGHT_START, DAYLIGHT_STEP};
+
+/// The maximum luminous efficacy of radiation, Km, in lm/W
+pub const MAX_LUMINOUS_EFFICACY: f32 = 683.0;
+
+/// A spectral distribution: values (spectral power, radiance, reflectance,
+/// transmittance, ...) sampled at increasing wavelengths in nm.
+///
+/// Between samples values are linearly interpolated. Outside the sampled
+/// range the nearest sample is used, as CIE 015 recommends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spd {
+    wavelengths: Vec<f32>,
+    values: Vec<f32>,
+}
+
+impl Spd {
+    /// Create from wavelengths (in nm, strictly increasing) and the values at
+    /// them. Panics if the lengths differ, or there are no samples.
+    pub fn new(wavelengths: Vec<f32>, values: Vec<f32>) -> Spd {
+        assert_eq!(wavelengths.len(), values.len());
+        assert!(!wavelengths.is_empty());
+        debug_assert!(wavelengths.windows(2).all(|w| w[0] < w[1]));
+        Spd {
+            wavelengths,
+            values
+        }
+    }
+
+    /// Create from values sampled every `step` nm from `start` nm
+    pub fn from_regular(start: f32, step: f32, values: &[f32]) -> Spd {
+        let wavelengths = (0..values.len()).map(|i| start + step * i as f32).collect();
+        Spd::new(wavelengths, values.to_vec())
+    }
+
+    /// Create by sampling a function of wavelength every `step` nm from
+    /// `start` to `end` nm (inclusive)
+    pub fn from_fn<F: Fn(f32) -> f32>(start: f32, end: f32, step: f32, f: F) -> Spd {
+        let n = ((end - start) / step).round() as usize + 1;
+        let wavelengths: Vec<f32> = (0..n).map(|i| start + step * i as f32).collect();
+        let values = wavelengths.iter().map(|&w| f(w)).collect();
+        Spd::new(wavelengths, values)
+    }
+
+    /// A constant spectrum (e.g. equal energy, or a perfect reflector) over
+    /// the visible range
+    pub fn constant(value: f32) -> Spd {
+        Spd::from_fn(380.0, 780.0, CMF_STEP, |_| value)
+    }
+
+    /// The spectral radiance of a blackbody, in W/(sr·m²·nm), from 380 nm to
+    /// 780 nm in 5 nm steps
+    pub fn blackbody(temperature: ColorTemp) -> Spd {
+        Spd::from_fn(380.0, 780.0, CMF_STEP, |w| temperature.spectral_radiance(w))
+    }
+
+    #[inline]
+    pub fn wavelengths(&self) -> &[f32] {
+        &self.wavelengths
+    }
+    #[inline]
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// The (interpolated) value at a wavelength in nm
+    pub fn value_at(&self, wavelength: f32) -> f32 {
+        let n = self.wavelengths.len();
+        if wavelength <= self.wavelengths[0] { return self.values[0]; }
+        if wavelength >= self.wavelengths[n - 1] { return self.values[n - 1]; }
+
+        // the first sample above the wavelength
+        let i = self.wavelengths.partition_point(|&w| w <= wavelength);
+        let (w0, w1) = (self.wavelengths[i - 1], self.wavelengths[i]);
+        let t = (wavelength - w0) / (w1 - w0);
+        self.values[i - 1] + (self.values[i] - self.values[i - 1]) * t
+    }
+
+    /// Resample every `step` nm from `start` to `end` nm (inclusive)
+    pub fn resample(&self, start: f32, end: f32, step: f32) -> Spd {
+        Spd::from_fn(start, end, step, |w| self.value_at(w))
+    }
+
+    /// Scale so that the value at `wavelength` is `value` (e.g. 100 at
+    /// 560 nm for illuminants)
+    pub fn normalized(&self, wavelength: f32, value: f32) -> Spd {
+        self.clone() * (value / self.value_at(wavelength))
+    }
+
+    /// Apply a function to every value
+    pub fn map<F: Fn(f32) -> f32>(&self, f: F) -> Spd {
+        Spd::new(self.wavelengths.clone(), self.values.iter().map(|&v| f(v)).collect())
+    }
+
+    // Weighted sums against a set of color matching functions, in nm
+    pub(crate) fn integrate(&self, cmf: &[[f32; 3]]) -> [f64; 3] {
+        let mut xyz = [0.0; 3];
+        for (i, c) in cmf.iter().enumerate() {
+            let p = self.value_at(cmf_wavelength(i)) as f64 * CMF_STEP as f64;
+            for k in 0..3 {
+                xyz[k] += p * c[k] as f64;
+            }
+        }
+        xyz
+    }
+
+    /// The tristimulus values of an emissive spectrum in W/(sr·m²·nm), so
+    /// that Y is the luminance in cd/m². I should be the white the color is
+    /// to be considered relative to.
+    pub fn emissive_cie1931<I: Illuminant>(&self) -> Cie1931<I> {
+        let xyz = self.integrate(&CIE1931_2DEG);
+        let k = MAX_LUMINOUS_EFFICACY as f64;
+        Cie1931::new((k * xyz[0]) as f32, (k * xyz[1]) as f32, (k * xyz[2]) as f32)
+    }
+
+    /// The tristimulus values of a reflectance (or transmittance) spectrum
+    /// lit by the `illuminant` spectrum, scaled so that a perfect reflector
+    /// has Y=1. I should be the illuminant that `illuminant` is.
+    pub fn reflective_cie1931<I: Illuminant>(&self, illuminant: &Spd) -> Cie1931<I> {
+        let lit = self.clone() * illuminant;
+        let xyz = lit.integrate(&CIE1931_2DEG);
+        let k = 1.0 / illuminant.integrate(&CIE1931_2DEG)[1];
+        Cie1931::new((k * xyz[0]) as f32, (k * xyz[1]) as f32, (k * xyz[2]) as f32)
+    }
+}
+
+impl From<Daylight> for Spd {
+    fn from(input: Daylight) -> Spd {
+        Spd::from_regular(DAYLIGHT_START, DAYLIGHT_STEP, &input.samples())
+    }
+}
+
+// Arithmetic is at the wavelengths of the left hand side, interpolating the
+// right hand side
+
+impl Add<&Spd> for Spd {
+    type Output = Spd;
+
+    fn add(mut self, rhs: &Spd) -> Spd {
+        for (v, &w) in self.values.iter_mut().zip(self.wavelengths.iter()) {
+            *v += rhs.value_at(w);
+        }
+        self
+    }
+}
+
+impl Mul<&Spd> for Spd {
+    type Output = Spd;
+
+    fn mul(mut self, rhs: &Spd) -> Spd {
+        for (v, &w) in self.values.iter_mut().zip(self.wavelengths.iter()) {
+            *v *= rhs.value_at(w);
+        }
+        self
+    }
+}
+
+impl Mul<f32> for Spd {
+    type Output = Spd;
+
+    fn mul(mut self, rhs: f32) -> Spd {
+        for v in self.values.iter_mut() {
+            *v *= rhs;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::{A, D65, E};
+    use crate::lab::Lab;
+
+    #[test]
+    fn test_interpolation() {
+        let spd = Spd::new(vec![400.0, 500.0, 700.0], vec![1.0, 3.0, 2.0]);
+        assert_eq!(spd.value_at(300.0), 1.0);
+        assert_eq!(spd.value_at(450.0), 2.0);
+        assert_eq!(spd.value_at(500.0), 3.0);
+        assert_eq!(spd.value_at(650.0), 2.25);
+        assert_eq!(spd.value_at(800.0), 2.0);
+
+        let resampled = spd.resample(400.0, 700.0, 50.0);
+        assert_eq!(resampled.values(), &[1.0, 2.0, 3.0, 2.75, 2.5, 2.25, 2.0]);
+
+        let sum = spd.clone() + &Spd::constant(1.0);
+        assert_eq!(sum.value_at(450.0), 3.0);
+        let product = spd * &Spd::constant(0.5) * 2.0;
+        assert_eq!(product.value_at(650.0), 2.25);
+    }
+
+    #[test]
+    fn test_white_points() {
+        // a perfect reflector under an illuminant is that illuminant's white
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        let white: Cie1931<D65> = Spd::constant(1.0).reflective_cie1931(&d65);
+        assert!((white.x() - 0.9505).abs() < 0.0005);
+        assert!((white.y() - 1.0).abs() < 0.00001);
+        assert!((white.z() - 1.0888).abs() < 0.0005);
+
+        let a = Spd::blackbody(ColorTemp::new(2856.0));
+        let white: Cie1931<A> = Spd::constant(1.0).reflective_cie1931(&a);
+        assert!((white.x() - 1.0985).abs() < 0.0005);
+        assert!((white.z() - 0.3558).abs() < 0.0005);
+
+        let white: Cie1931<E> = Spd::constant(1.0).reflective_cie1931(&Spd::constant(1.0));
+        assert!((white.x() - 1.0).abs() < 0.0005);
+        assert!((white.z() - 1.0).abs() < 0.0005);
+    }
+
+    #[test]
+    fn test_emissive() {
+        // 1 W/(sr·m²·nm) everywhere is 683 lm/W times the integral of ȳ
+        let xyz: Cie1931<E> = Spd::constant(1.0).emissive_cie1931();
+        assert!((xyz.y() - 683.0 * 106.857).abs() < 1.0);
+        assert!((xyz.x() - xyz.y()).abs() < 1.0);
+
+        // a grey reflector
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        let grey: Cie1931<D65> = Spd::constant(0.18).reflective_cie1931(&d65);
+        let lab: Lab<D65> = From::from(grey);
+        assert!((lab.l() - 49.5).abs() < 0.1);
+        assert!(lab.a().abs() < 0.05);
+        assert!(lab.b().abs() < 0.05);
+    }
+}
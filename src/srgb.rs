@@ -1,6 +1,7 @@
 
-use siege_math::{Vec2, Vec3, Mat3};
-use crate::cie1931::{Cie1931, D65};
+use siege_math::Vec2;
+use crate::cie1931::D65;
+use crate::rgb::{Rgb, RgbPrimaries, TransferFunction, Linear};
 use super::Chromaticity;
 
 pub const SRGB_CHROMATICITIES: Chromaticity = Chromaticity {
@@ -39,65 +40,45 @@ impl From<Srgb24> for Srgb {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Srgb {
-    pub v: Vec3<f32>
-}
-
-impl Srgb {
-    pub fn new(r: f32, g: f32, b: f32) -> Srgb {
-        Srgb {
-            v: Vec3::new(r, g, b)
-        }
-    }
+/// The sRGB primaries, with the D65 white point
+#[derive(Debug, Clone, Copy)]
+pub struct SrgbPrimaries;
 
-    #[inline]
-    pub fn r(&self) -> f32 {
-        self.v.x
-    }
-    #[inline]
-    pub fn g(&self) -> f32 {
-        self.v.y
-    }
-    #[inline]
-    pub fn b(&self) -> f32 {
-        self.v.z
-    }
+impl RgbPrimaries for SrgbPrimaries {
+    type White = D65;
+    const CHROMATICITY: Chromaticity = SRGB_CHROMATICITIES;
 }
 
-#[derive(Debug, Clone)]
-pub struct LinearSrgb {
-    pub v: Vec3<f32>
-}
+/// The sRGB transfer function (IEC 61966-2-1).
+/// Results are clamped at 1.0 in both directions.
+#[derive(Debug, Clone, Copy)]
+pub struct SrgbTransfer;
 
-impl LinearSrgb {
-    pub fn new(r: f32, g: f32, b: f32) -> LinearSrgb {
-        LinearSrgb {
-            v: Vec3::new(r, g, b)
-        }
+impl TransferFunction for SrgbTransfer {
+    // This applies gamma correction
+    fn encode(x: f32) -> f32 {
+        let a = 0.055;
+        let e = if x <= 0.0031308 { 12.92 * x }
+        else { (1.0 + a) * x.powf(1.0/2.4) - a };
+        e.min(1.0)
     }
 
-    #[inline]
-    pub fn r(&self) -> f32 {
-        self.v.x
-    }
-    #[inline]
-    pub fn g(&self) -> f32 {
-        self.v.y
-    }
-    #[inline]
-    pub fn b(&self) -> f32 {
-        self.v.z
+    // Perhaps use the method here instead:
+    //   https://en.wikipedia.org/w/index.php?title=CIE_1931_color_space&action=edit&section=13
+    fn decode(x: f32) -> f32 {
+        let l = if x <= 0.04045 { x / 12.92 }
+        else { ((x + 0.055)/1.055).powf(2.4) };
+        l.min(1.0)
     }
+}
 
-    pub fn get_luminance(&self) -> f32
-    {
-        // middle row of From<LinearSrgb> for Cie1931
-        Vec3::<f32>::new(0.2126729,
-                         0.7151522,
-                         0.0721750).dot(self.v)
-    }
+/// sRGB, gamma encoded
+pub type Srgb = Rgb<SrgbPrimaries, SrgbTransfer>;
 
+/// sRGB with linear transfer characteristics
+pub type LinearSrgb = Rgb<SrgbPrimaries, Linear>;
+
+impl LinearSrgb {
     // For luminance values > 1.0, this saturates to white (e.g. each
     // channel scales linearly and then clamps at 1.0)
     pub fn set_luminance(&mut self, luminance: f32) {
@@ -131,112 +112,33 @@ impl LinearSrgb {
     }
 }
 
-// Perhaps use the method here instead:
-impl From<Cie1931<D65>> for LinearSrgb {
-    fn from(input: Cie1931<D65>) -> LinearSrgb {
-        // Concepts from (but not data):
-        //   From https://en.wikipedia.org/wiki/SRGB and
-        //   https://www.image-engineering.de/library/technotes/958-how-to-convert-between-srgb-and-ciexyz
-        // Exact figures from http://www.color.org/chardata/rgb/srgb.xalter
-        // Assuming: a 2° standard colorimetric observer for CIE XYZ
-        //           D65 White Point (x = 0.3127, y = 0.3290, z = 0.3583)
-        //           White Point Luminance: 80 cd/m^2
-
-        // Each display device is different. Exact values differ depending on the
-        // source you are looking at. Physically Based Rendering book as slightly
-        // different values using RGB definitions for HD television.
-        //    3.240479  -1.537150  -0.498535
-        //   -0.969256   1.875991   0.041556
-        //    0.055648  -0.204043   1.057311
-
-        /*
-        // Tristimulus value normalization (sRGB spec section 6)
-        // PRESUMES Y=100 !!!!!
-        let n: Vec3<f32> = Vec3::new(
-            input.v.x - 0.1901,
-            input.v.y - 0.2,
-            input.v.z - 0.2178) * 0.0125313;
-         */
-
-        let m: Mat3<f32> = Mat3::new(
-            3.2406255, -1.5372080, -0.49862860,
-            -0.96893071, 1.8757561, 0.041517524,
-            0.055710120, -0.20402105, 1.0569959
-        );
+// The conversions to and from Cie1931<D65> are provided generically for Rgb.
+// The matrices are derived from SRGB_CHROMATICITIES.
+// Concepts from (but not data):
+//   From https://en.wikipedia.org/wiki/SRGB and
+//   https://www.image-engineering.de/library/technotes/958-how-to-convert-between-srgb-and-ciexyz
+// Assuming: a 2° standard colorimetric observer for CIE XYZ (not Cie1964)
+//           D65 White Point (x = 0.3127, y = 0.3290, z = 0.3583)
+//           White Point Luminance: 80 cd/m^2
+// The derived matrices agree with http://www.color.org/chardata/rgb/srgb.xalter
+// to about 3 places. Be warned, ICC profiles use D50.
+//
+// Each display device is different. Exact values differ depending on the
+// source you are looking at. Physically Based Rendering book uses slightly
+// different values using RGB definitions for HD television.
+//     0.412453   0.357580   0.180423
+//     0.212671   0.715160   0.072169
+//     0.019334   0.119193   0.950227
 
-        LinearSrgb {
-            v: &m * &input.v
-        }
-    }
-}
-
-impl From<LinearSrgb> for Cie1931<D65> {
-    fn from(input: LinearSrgb) -> Cie1931<D65> {
-        // From https://en.wikipedia.org/wiki/SRGB and
-        // https://www.image-engineering.de/library/technotes/958-how-to-convert-between-srgb-and-ciexyz
-        // Reference point of D65 (as defined by sRGB) -- be warned, ICC profiles use D50.
-        // (These values are exact. The above matrix is an inverse of this.)
-        let m: Mat3<f32> = Mat3::new(
-            0.4124, 0.3576, 0.1805,
-            0.2126, 0.7152, 0.0722,
-            0.0193, 0.1192, 0.9505
-        );
-
-        // Physically Based Rendering book uses slightly different values using RGB
-        // definitions for HD television.
-        //     0.412453   0.357580   0.180423
-        //     0.212671   0.715160   0.072169
-        //     0.019334   0.119193   0.950227
-
-        let cv = &m * &input.v;
-
-        Cie1931::new(cv.x, cv.y, cv.z)
-    }
-
-}
-
-// Perhaps use the method here instead:
-//   https://en.wikipedia.org/w/index.php?title=CIE_1931_color_space&action=edit&section=13
 impl From<Srgb> for LinearSrgb {
     fn from(srgb: Srgb) -> LinearSrgb {
-        let f = |x: f32| -> f32 {
-            if x <= 0.04045 { x / 12.92 }
-            else { ((x + 0.055)/1.055).powf(2.4) }
-        };
-
-        let mut red = f(srgb.r());
-        if red>1.0 { red = 1.0 };
-
-        let mut green = f(srgb.g());
-        if green>1.0 { green = 1.0 };
-
-        let mut blue = f(srgb.b());
-        if blue>1.0 { blue = 1.0 };
-
-        LinearSrgb::new(red, green, blue)
+        srgb.to_linear()
     }
 }
 
 impl From<LinearSrgb> for Srgb {
-    // This applies gamma correction
-    // Assumes the 2-degree colorimetric observer
     fn from(s: LinearSrgb) -> Srgb {
-        let a = 0.055;
-        let f = |x: f32| -> f32 {
-            if x <= 0.0031308 { 12.92 * x }
-            else { (1.0 + a) * x.powf(1.0/2.4) - a }
-        };
-
-        let mut red = f(s.r());
-        if red>1.0 { red = 1.0 };
-
-        let mut green = f(s.g());
-        if green>1.0 { green = 1.0 };
-
-        let mut blue = f(s.b());
-        if blue>1.0 { blue = 1.0 };
-
-        Srgb::new(red, green, blue)
+        Srgb::from_linear(s)
     }
 }
 
@@ -244,15 +146,14 @@ impl From<LinearSrgb> for Srgb {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use siege_math::Vec3;
     use crate::cie1931::{Cie1931, D65};
     use float_cmp::ApproxEq;
 
     #[test]
     fn test_scale_brightness() {
 
-        let mut x = LinearSrgb {
-            v: Vec3::new( 0.5, 0.1, 0.4 )
-        };
+        let mut x = LinearSrgb::new(0.5, 0.1, 0.4);
 
         x.set_brightness(0.8);
         assert!(x.get_brightness() > 0.799999);
@@ -274,7 +175,7 @@ mod tests {
     #[test]
     fn test_to_and_from_cie1931() {
         let lsrgb = LinearSrgb::new(0.5, 0.2, 0.7);
-        let xyz: Cie1931<D65> = From::from(lsrgb.clone());
+        let xyz: Cie1931<D65> = From::from(lsrgb);
         let lsrgb2: LinearSrgb = From::from(xyz);
 
         assert!(
@@ -301,7 +202,7 @@ mod tests {
     fn test_to_and_from_linear() {
         let srgb = Srgb::new(0.1245, 0.0924, 0.9812);
 
-        let l: LinearSrgb = From::from(srgb.clone());
+        let l: LinearSrgb = From::from(srgb);
         let srgb2: Srgb = From::from(l);
 
         assert!(
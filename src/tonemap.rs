@@ -0,0 +1,258 @@
+// Tone mapping: compressing scene-linear colors into the 0..1 range of an
+// SDR display.
+//
+// The operators work on linear sRGB (Rec.709 primaries), per channel unless
+// noted, and give linear display values which can then be encoded as sRGB.
+
+use crate::aces::AcesCg;
+use crate::aces_output::aces_fitted;
+use crate::matrix::{self, M3};
+use crate::srgb::{LinearSrgb, Srgb};
+
+/// A tone mapping operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapOperator {
+    /// Reinhard, x / (1 + x). Never reaches white unless given a finite white
+    /// point, which it is scaled to.
+    Reinhard,
+    /// Reinhard with a white point, x (1 + x / W²) / (1 + x), which reaches
+    /// white at W
+    ReinhardExtended,
+    /// John Hable's filmic curve from Uncharted 2
+    Hable,
+    /// Jim Hejl and Richard Burgess-Dawson's filmic curve
+    HejlBurgess,
+    /// Stephen Hill's fit of the ACES RRT and sRGB ODT
+    AcesFitted,
+    /// Troy Sobotka's AgX, with the minimal polynomial approximation of its
+    /// default contrast curve (not per channel)
+    AgX,
+    /// Khronos PBR Neutral, which keeps colors as they are below a highlight
+    /// compression threshold (not per channel)
+    PbrNeutral,
+}
+
+impl ToneMapOperator {
+    /// The white point that the operator is usually used with. This is
+    /// infinite for operators with their own shoulder.
+    pub fn default_white_point(&self) -> f32 {
+        match *self {
+            ToneMapOperator::ReinhardExtended => 4.0,
+            ToneMapOperator::Hable => 11.2,
+            _ => f32::INFINITY,
+        }
+    }
+}
+
+/// A tone mapping operator with its parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMap {
+    pub operator: ToneMapOperator,
+    /// Exposure adjustment in stops, applied before the curve
+    pub exposure: f32,
+    /// The (exposed) scene-linear value that maps to display white. Where
+    /// finite, the output of the curve is scaled so that it is reached. Zero
+    /// or less is taken as infinite.
+    pub white_point: f32,
+}
+
+impl ToneMap {
+    /// The operator with no exposure adjustment and its default white point
+    pub fn new(operator: ToneMapOperator) -> ToneMap {
+        ToneMap {
+            operator,
+            exposure: 0.0,
+            white_point: operator.default_white_point()
+        }
+    }
+
+    pub fn with_exposure(mut self, exposure: f32) -> ToneMap {
+        self.exposure = exposure;
+        self
+    }
+
+    pub fn with_white_point(mut self, white_point: f32) -> ToneMap {
+        self.white_point = white_point;
+        self
+    }
+
+    /// Tone map to linear display values in 0..1
+    pub fn apply(&self, input: &LinearSrgb) -> LinearSrgb {
+        let scale = 2.0_f64.powf(self.exposure as f64);
+        let rgb = [input.r() as f64 * scale, input.g() as f64 * scale,
+                   input.b() as f64 * scale];
+        let mut out = self.curve(rgb);
+
+        let w = self.white();
+        if w.is_finite() && self.operator != ToneMapOperator::ReinhardExtended {
+            let white = self.curve([w, w, w]);
+            for (o, w) in out.iter_mut().zip(white.iter()) {
+                *o /= w;
+            }
+        }
+        let out = out.map(|c| c.clamp(0.0, 1.0));
+        LinearSrgb::new(out[0] as f32, out[1] as f32, out[2] as f32)
+    }
+
+    /// Tone map ACEScg, by way of linear sRGB
+    pub fn apply_acescg(&self, input: &AcesCg) -> LinearSrgb {
+        self.apply(&input.convert())
+    }
+
+    /// Tone map and encode for an sRGB display
+    pub fn apply_srgb(&self, input: &LinearSrgb) -> Srgb {
+        Srgb::from_linear(self.apply(input))
+    }
+
+    fn white(&self) -> f64 {
+        if self.white_point > 0.0 { self.white_point as f64 } else { f64::INFINITY }
+    }
+
+    fn curve(&self, rgb: [f64; 3]) -> [f64; 3] {
+        match self.operator {
+            ToneMapOperator::Reinhard => rgb.map(|x| x / (1.0 + x)),
+            ToneMapOperator::ReinhardExtended => {
+                let w2 = self.white().powi(2);
+                rgb.map(|x| x * (1.0 + x / w2) / (1.0 + x))
+            },
+            ToneMapOperator::Hable => rgb.map(hable),
+            ToneMapOperator::HejlBurgess => rgb.map(|x| {
+                let x = (x - 0.004).max(0.0);
+                // the curve includes a 1/2.2 gamma, which is removed
+                ((x * (6.2 * x + 0.5)) / (x * (6.2 * x + 1.7) + 0.06)).powf(2.2)
+            }),
+            ToneMapOperator::AcesFitted => aces_fitted(rgb),
+            ToneMapOperator::AgX => agx(rgb),
+            ToneMapOperator::PbrNeutral => pbr_neutral(rgb),
+        }
+    }
+}
+
+fn hable(x: f64) -> f64 {
+    const A: f64 = 0.15; // shoulder strength
+    const B: f64 = 0.50; // linear strength
+    const C: f64 = 0.10; // linear angle
+    const D: f64 = 0.20; // toe strength
+    const E: f64 = 0.02; // toe numerator
+    const F: f64 = 0.30; // toe denominator
+    (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F
+}
+
+fn agx(rgb: [f64; 3]) -> [f64; 3] {
+    // the "inset" into a smaller gamut, and the "outset" back
+    const INSET: M3 = [
+        [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+        [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+        [0.0423756549057051, 0.0784336, 0.879142973793104],
+    ];
+    const OUTSET: M3 = [
+        [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+        [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+        [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+    ];
+    // the range of the log encoding, in stops relative to 1.0
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    let v = matrix::mul_vec(&INSET, rgb).map(|c| {
+        let x = (c.max(1e-10).log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV);
+        let (x2, x4) = (x * x, x * x * x * x);
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2
+            + 0.1191 * x - 0.00232
+    });
+    matrix::mul_vec(&OUTSET, v).map(|c| c.max(0.0).powf(2.2))
+}
+
+fn pbr_neutral(rgb: [f64; 3]) -> [f64; 3] {
+    const START_COMPRESSION: f64 = 0.8 - 0.04;
+    const DESATURATION: f64 = 0.15;
+
+    let x = rgb[0].min(rgb[1]).min(rgb[2]);
+    let offset = if x < 0.08 { x - 6.25 * x * x } else { 0.04 };
+    let rgb = rgb.map(|c| c - offset);
+
+    let peak = rgb[0].max(rgb[1]).max(rgb[2]);
+    if peak < START_COMPRESSION {
+        return rgb;
+    }
+    let d = 1.0 - START_COMPRESSION;
+    let new_peak = 1.0 - d * d / (peak + d - START_COMPRESSION);
+    let g = 1.0 - 1.0 / (DESATURATION * (peak - new_peak) + 1.0);
+    rgb.map(|c| c * new_peak / peak * (1.0 - g) + new_peak * g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATORS: [ToneMapOperator; 7] = [
+        ToneMapOperator::Reinhard, ToneMapOperator::ReinhardExtended,
+        ToneMapOperator::Hable, ToneMapOperator::HejlBurgess,
+        ToneMapOperator::AcesFitted, ToneMapOperator::AgX,
+        ToneMapOperator::PbrNeutral,
+    ];
+
+    fn grey(tonemap: &ToneMap, x: f32) -> f32 {
+        tonemap.apply(&LinearSrgb::new(x, x, x)).g()
+    }
+
+    #[test]
+    fn test_operators() {
+        let reinhard = ToneMap::new(ToneMapOperator::Reinhard);
+        assert!((grey(&reinhard, 1.0) - 0.5).abs() < 0.00001);
+
+        let extended = ToneMap::new(ToneMapOperator::ReinhardExtended);
+        assert!((grey(&extended, 4.0) - 1.0).abs() < 0.00001);
+        assert!((grey(&extended.with_white_point(f32::INFINITY), 1.0) - 0.5).abs() < 0.00001);
+
+        let hable = ToneMap::new(ToneMapOperator::Hable);
+        assert!((grey(&hable, 11.2) - 1.0).abs() < 0.00001);
+
+        // PBR Neutral leaves dark colors alone, apart from a small offset
+        let neutral = ToneMap::new(ToneMapOperator::PbrNeutral);
+        assert!((grey(&neutral, 0.5) - 0.46).abs() < 0.00001);
+
+        for &operator in OPERATORS.iter() {
+            let tonemap = ToneMap::new(operator);
+
+            // black stays (near) black, and mid grey is somewhere sensible
+            assert!(grey(&tonemap, 0.0) < 0.001);
+            let mid = grey(&tonemap, 0.18);
+            assert!(mid > 0.05 && mid < 0.3);
+
+            // monotonic, and within range
+            let mut last = 0.0;
+            for i in -40..40 {
+                let y = grey(&tonemap, 2.0_f32.powf(i as f32 / 4.0));
+                assert!(y >= last && y <= 1.0);
+                last = y;
+            }
+
+            // greys stay grey
+            let v = tonemap.apply(&LinearSrgb::new(0.7, 0.7, 0.7));
+            assert!((v.r() - v.g()).abs() < 0.001 && (v.b() - v.g()).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_parameters() {
+        for &operator in OPERATORS.iter() {
+            // a stop of exposure is double the input
+            let tonemap = ToneMap::new(operator);
+            assert!((grey(&tonemap.with_exposure(1.0), 0.1) - grey(&tonemap, 0.2)).abs() < 0.00001);
+
+            // the white point maps to white
+            let tonemap = tonemap.with_white_point(2.0);
+            assert!((grey(&tonemap, 2.0) - 1.0).abs() < 0.0001);
+            assert!(grey(&tonemap, 1.0) < 1.0);
+
+            // a white point of zero or less is infinite
+            let infinite = tonemap.with_white_point(f32::INFINITY);
+            for &w in &[0.0, -1.0] {
+                let tonemap = tonemap.with_white_point(w);
+                assert_eq!(grey(&tonemap, 0.5), grey(&infinite, 0.5));
+                assert!(!grey(&tonemap, 0.0).is_nan());
+            }
+        }
+    }
+}
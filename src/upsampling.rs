@@ -0,0 +1,440 @@
+// Spectral upsampling: finding a smooth reflectance spectrum for a color.
+//
+// After Jakob & Hanika, "A Low-Dimensional Function Space for Efficient
+// Spectral Upsampling", Computer Graphics Forum 38 (2019). The spectrum is a
+// sigmoid of a quadratic in wavelength, which is smooth and stays within 0..1,
+// so it is always a physically plausible reflectance.
+
+use std::sync::OnceLock;
+use crate::cie1931::{Cie1931, Illuminant};
+use crate::cmf::{CIE1931_2DEG, cmf_wavelength};
+use crate::colortemp::ColorTemp;
+use crate::daylight::Daylight;
+use crate::spd::Spd;
+use crate::rgb::RgbPrimaries;
+use crate::srgb::{LinearSrgb, SrgbPrimaries};
+use crate::matrix::{self, M3};
+
+// The fit is done with wavelength normalized to 0..1 over 380..780 nm
+const LAMBDA_MIN: f64 = 380.0;
+const LAMBDA_RANGE: f64 = 400.0;
+
+// The squared CIELAB residual (ΔE²) below which a fit has converged
+const TOLERANCE: f64 = 1e-8;
+
+// The resolution of each axis of the coefficient table
+const TABLE_RES: usize = 32;
+
+/// A reflectance spectrum S(λ) = σ(c0·λ² + c1·λ + c2), with λ in nm and
+/// σ(x) = ½ + x / (2·√(1 + x²)).
+#[derive(Debug, Clone, Copy)]
+pub struct SigmoidSpectrum {
+    pub c: [f32; 3]
+}
+
+#[inline]
+fn sigmoid(x: f64) -> f64 {
+    if x.is_infinite() {
+        if x > 0.0 { 1.0 } else { 0.0 }
+    } else {
+        0.5 + x / (2.0 * (1.0 + x * x).sqrt())
+    }
+}
+
+impl SigmoidSpectrum {
+    pub fn new(c0: f32, c1: f32, c2: f32) -> SigmoidSpectrum {
+        SigmoidSpectrum {
+            c: [c0, c1, c2]
+        }
+    }
+
+    /// The reflectance at `wavelength` (in nm)
+    pub fn value_at(&self, wavelength: f32) -> f32 {
+        let l = wavelength as f64;
+        let x = (self.c[0] as f64 * l + self.c[1] as f64) * l + self.c[2] as f64;
+        sigmoid(x) as f32
+    }
+
+    /// Sample from 380 nm to 780 nm in 5 nm steps
+    pub fn to_spd(self) -> Spd {
+        Spd::from_fn(380.0, 780.0, 5.0, |w| self.value_at(w))
+    }
+
+    /// Fit a spectrum that, lit by the `illuminant` spectrum, has the
+    /// tristimulus values `target` (relative to a perfect reflector at Y=1).
+    ///
+    /// The target must be a reflectance color, inside the object color solid
+    /// of the illuminant; colors at its boundary (such as those with a zero
+    /// component in RGB) are only approached. Where the fit from a flat grey
+    /// spectrum stalls, it is continued from the grey towards the target.
+    pub fn fit<I: Illuminant>(target: &Cie1931<I>, illuminant: &Spd) -> SigmoidSpectrum {
+        let fit = Fit::new(illuminant);
+        let target = fit.lab([target.x() as f64, target.y() as f64, target.z() as f64]);
+        SigmoidSpectrum::from_normalized(fit.solve(&target).0)
+    }
+
+    // from coefficients over the normalized wavelength to nm
+    fn from_normalized(t: [f64; 3]) -> SigmoidSpectrum {
+        let (a, s) = (LAMBDA_MIN, LAMBDA_RANGE);
+        SigmoidSpectrum::new(
+            (t[0] / (s * s)) as f32,
+            (t[1] / s - 2.0 * a * t[0] / (s * s)) as f32,
+            (t[0] * a * a / (s * s) - t[1] * a / s + t[2]) as f32
+        )
+    }
+}
+
+// The spectrum for a color under D65 daylight, interpolated from a table of
+// fits that is built on first use. Components are clamped to 0..1.
+impl From<LinearSrgb> for SigmoidSpectrum {
+    fn from(input: LinearSrgb) -> SigmoidSpectrum {
+        TABLE.get_or_init(Table::new).fetch([input.r(), input.g(), input.b()])
+    }
+}
+
+static TABLE: OnceLock<Table> = OnceLock::new();
+
+// Jakob & Hanika's table of the (normalized) coefficients for linear sRGB
+// under D65. A color is indexed by which component is largest, its value z,
+// and the other two relative to it.
+struct Table {
+    scale: Vec<f64>,
+    // [largest component][z][y][x]
+    coefficients: Vec<[f64; 3]>,
+}
+
+impl Table {
+    fn new() -> Table {
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        let fit = Fit::new(&d65);
+        let n = TABLE_RES;
+        let step = 1.0 / (n - 1) as f64;
+
+        // each axis is finer near 0 and 1
+        let scale: Vec<f64> = (0..n).map(|k| smoothstep(k as f64 * step)).collect();
+        let mut coefficients = vec![[0.0; 3]; 3 * n * n * n];
+
+        // Fit each line of z from a dark value out to black and to white,
+        // starting each fit from its neighbour's solution. The first fits of
+        // the lines start from their neighbours in turn, out from the grey.
+        let start = n / 5;
+        for l in 0..3 {
+            let mut first = [0.0; 3];
+            for j in 0..n {
+                let mut row = first;
+                for i in 0..n {
+                    let target = |k: usize| {
+                        // black itself has no finite solution
+                        let z = scale[k].max(1e-6);
+                        let mut rgb = [0.0; 3];
+                        rgb[l] = z;
+                        rgb[(l + 1) % 3] = z * scale[i];
+                        rgb[(l + 2) % 3] = z * scale[j];
+                        fit.lab(matrix::mul_vec(&<SrgbPrimaries as RgbPrimaries>::RGB_TO_XYZ, rgb))
+                    };
+                    let index = |k: usize| ((l * n + k) * n + j) * n + i;
+
+                    row = if i == 0 && j == 0 { fit.solve(&target(start)).0 }
+                          else { fit.solve_from(row, &target(start)) };
+                    if i == 0 {
+                        first = row;
+                    }
+                    coefficients[index(start)] = row;
+
+                    let mut c = row;
+                    for k in start + 1..n {
+                        c = fit.solve_from(c, &target(k));
+                        coefficients[index(k)] = c;
+                    }
+                    let mut c = row;
+                    for k in (0..start).rev() {
+                        c = fit.solve_from(c, &target(k));
+                        coefficients[index(k)] = c;
+                    }
+                }
+            }
+        }
+
+        Table { scale, coefficients }
+    }
+
+    fn fetch(&self, rgb: [f32; 3]) -> SigmoidSpectrum {
+        let rgb = rgb.map(|c| (c as f64).clamp(0.0, 1.0));
+        let l = if rgb[0] >= rgb[1] && rgb[0] >= rgb[2] { 0 }
+                else if rgb[1] >= rgb[2] { 1 }
+                else { 2 };
+        let z = rgb[l];
+        if z == 0.0 {
+            return SigmoidSpectrum::new(0.0, 0.0, f32::NEG_INFINITY);
+        }
+
+        let n = TABLE_RES;
+        let (xi, fx) = self.locate(rgb[(l + 1) % 3] / z);
+        let (yi, fy) = self.locate(rgb[(l + 2) % 3] / z);
+        let (zi, fz) = self.locate(z);
+
+        // trilinear interpolation
+        let mut c = [0.0; 3];
+        for (dk, wz) in [(0, 1.0 - fz), (1, fz)] {
+            for (dj, wy) in [(0, 1.0 - fy), (1, fy)] {
+                for (di, wx) in [(0, 1.0 - fx), (1, fx)] {
+                    let cell = &self.coefficients[((l * n + zi + dk) * n + yi + dj) * n + xi + di];
+                    for (c, v) in c.iter_mut().zip(cell.iter()) {
+                        *c += wx * wy * wz * v;
+                    }
+                }
+            }
+        }
+        SigmoidSpectrum::from_normalized(c)
+    }
+
+    // The cell of the scale containing `v`, and the fraction of the way
+    // across it
+    fn locate(&self, v: f64) -> (usize, f64) {
+        let i = self.scale.partition_point(|&s| s <= v).clamp(1, TABLE_RES - 1) - 1;
+        (i, (v - self.scale[i]) / (self.scale[i + 1] - self.scale[i]))
+    }
+}
+
+#[inline]
+fn smoothstep(x: f64) -> f64 {
+    x * x * (3.0 - 2.0 * x)
+}
+
+// Gauss-Newton (Levenberg-Marquardt) fit of the coefficients, in CIELAB so
+// that the residual is perceptual
+struct Fit {
+    // normalized wavelength, and illuminant × cmf / normalization
+    samples: Vec<(f64, [f64; 3])>,
+    white: [f64; 3],
+}
+
+impl Fit {
+    fn new(illuminant: &Spd) -> Fit {
+        let mut samples: Vec<(f64, [f64; 3])> = CIE1931_2DEG.iter().enumerate()
+            .map(|(i, cmf)| {
+                let w = cmf_wavelength(i);
+                let p = illuminant.value_at(w) as f64;
+                ((w as f64 - LAMBDA_MIN) / LAMBDA_RANGE,
+                 [p * cmf[0] as f64, p * cmf[1] as f64, p * cmf[2] as f64])
+            })
+            .collect();
+
+        let norm: f64 = samples.iter().map(|s| s.1[1]).sum();
+        let mut white = [0.0; 3];
+        for s in samples.iter_mut() {
+            for (w, v) in white.iter_mut().zip(s.1.iter_mut()) {
+                *v /= norm;
+                *w += *v;
+            }
+        }
+
+        Fit { samples, white }
+    }
+
+    fn lab(&self, xyz: [f64; 3]) -> [f64; 3] {
+        let f = |t: f64| -> f64 {
+            if t > 216.0 / 24389.0 { t.cbrt() }
+            else { (24389.0 / 27.0 * t + 16.0) / 116.0 }
+        };
+        let fx = f(xyz[0] / self.white[0]);
+        let fy = f(xyz[1] / self.white[1]);
+        let fz = f(xyz[2] / self.white[2]);
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    // d(Lab)/dc, through d(XYZ)/dc and d(Lab)/d(XYZ)
+    fn jacobian(&self, c: &[f64; 3]) -> M3 {
+        let mut xyz = [0.0; 3];
+        let mut dxyz = [[0.0; 3]; 3];
+        for &(t, w) in self.samples.iter() {
+            let x = (c[0] * t + c[1]) * t + c[2];
+            let r = sigmoid(x);
+            let q = 1.0 + x * x;
+            let dr = if x.is_infinite() { 0.0 } else { 0.5 / (q * q.sqrt()) };
+            let d = [dr * t * t, dr * t, dr];
+            for k in 0..3 {
+                xyz[k] += r * w[k];
+                dxyz[k][0] += d[0] * w[k];
+                dxyz[k][1] += d[1] * w[k];
+                dxyz[k][2] += d[2] * w[k];
+            }
+        }
+
+        let df = |t: f64| -> f64 {
+            if t > 216.0 / 24389.0 { 1.0 / (3.0 * t.cbrt() * t.cbrt()) }
+            else { 24389.0 / 27.0 / 116.0 }
+        };
+        let dfx = df(xyz[0] / self.white[0]) / self.white[0];
+        let dfy = df(xyz[1] / self.white[1]) / self.white[1];
+        let dfz = df(xyz[2] / self.white[2]) / self.white[2];
+        let dlab = [
+            [0.0, 116.0 * dfy, 0.0],
+            [500.0 * dfx, -500.0 * dfy, 0.0],
+            [0.0, 200.0 * dfy, -200.0 * dfz],
+        ];
+        matrix::mul(&dlab, &dxyz)
+    }
+
+    fn residual(&self, c: &[f64; 3], target: &[f64; 3]) -> [f64; 3] {
+        let mut xyz = [0.0; 3];
+        for &(t, w) in self.samples.iter() {
+            let r = sigmoid((c[0] * t + c[1]) * t + c[2]);
+            xyz = [xyz[0] + r * w[0], xyz[1] + r * w[1], xyz[2] + r * w[2]];
+        }
+        let lab = self.lab(xyz);
+        [lab[0] - target[0], lab[1] - target[1], lab[2] - target[2]]
+    }
+
+    // Fit the CIELAB `target` from a flat grey spectrum, giving the solution
+    // and its squared residual
+    fn solve(&self, target: &[f64; 3]) -> ([f64; 3], f64) {
+        // Greys have an exact flat solution
+        let y = (target[0] + 16.0) / 116.0;
+        let y = if y > 6.0 / 29.0 { y * y * y } else { target[0] * 27.0 / 24389.0 };
+        let grey = 2.0 * y.clamp(0.0, 1.0) - 1.0;
+        let start = [0.0, 0.0, grey / (1.0 - grey * grey).sqrt()];
+        if start[2].is_infinite() || (target[1].abs() < 1e-9 && target[2].abs() < 1e-9) {
+            return (start, 0.0);
+        }
+
+        // Straight from the grey is usually enough
+        let mut best = self.refine(start, target);
+
+        // Otherwise it found a local minimum. Continue from the grey towards
+        // the target in steps of chroma, starting each from the last solution,
+        // in finer steps until it converges.
+        for &steps in &[8, 64, 512] {
+            if best.1 < TOLERANCE { break; }
+            let mut c = start;
+            for i in 1..=steps {
+                let t = i as f64 / steps as f64;
+                c = self.refine(c, &[target[0], target[1] * t, target[2] * t]).0;
+            }
+            let next = self.refine(c, target);
+            if next.1 < best.1 {
+                best = next;
+            }
+        }
+        best
+    }
+
+    // Fit the CIELAB `target` from a nearby solution, or from grey if that
+    // stalls
+    fn solve_from(&self, seed: [f64; 3], target: &[f64; 3]) -> [f64; 3] {
+        let near = self.refine(seed, target);
+        if near.1 < TOLERANCE {
+            return near.0;
+        }
+        let far = self.solve(target);
+        if far.1 < near.1 { far.0 } else { near.0 }
+    }
+
+    // Gauss-Newton (Levenberg-Marquardt) from the coefficients `c`, giving the
+    // solution and its squared residual
+    fn refine(&self, mut c: [f64; 3], target: &[f64; 3]) -> ([f64; 3], f64) {
+        let norm = |r: &[f64; 3]| r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+        let mut r = self.residual(&c, target);
+        let mut mu = 1e-3;
+        for _ in 0..200 {
+            if norm(&r) < TOLERANCE { break; }
+
+            let j = self.jacobian(&c);
+
+            // JᵀJ and Jᵀr
+            let mut jtj = [[0.0; 3]; 3];
+            let mut jtr = [0.0; 3];
+            for a in 0..3 {
+                for b in 0..3 {
+                    jtj[a][b] = (0..3).map(|k| j[k][a] * j[k][b]).sum();
+                }
+                jtr[a] = (0..3).map(|k| j[k][a] * r[k]).sum();
+            }
+
+            // Damped step, increasing the damping until the residual falls
+            let mut improved = false;
+            while mu < 1e12 {
+                let mut m = jtj;
+                for (a, row) in m.iter_mut().enumerate() {
+                    row[a] += mu * jtj[a][a].max(1e-12);
+                }
+                let step = matrix::mul_vec(&matrix::invert(&m), jtr);
+                let next = [c[0] - step[0], c[1] - step[1], c[2] - step[2]];
+                let rn = self.residual(&next, target);
+                if norm(&rn) < norm(&r) {
+                    c = next;
+                    r = rn;
+                    mu = (mu / 3.0).max(1e-9);
+                    improved = true;
+                    break;
+                }
+                mu *= 3.0;
+            }
+            if !improved { break; }
+        }
+        (c, norm(&r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cie1931::D65;
+
+    fn round_trip(input: LinearSrgb, spectrum: SigmoidSpectrum, tolerance: f32) {
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        let xyz: Cie1931<D65> = spectrum.to_spd().reflective_cie1931(&d65);
+        let back: LinearSrgb = From::from(xyz);
+        assert!((back.r() - input.r()).abs() < tolerance, "{:?}: {:?}", input, back);
+        assert!((back.g() - input.g()).abs() < tolerance, "{:?}: {:?}", input, back);
+        assert!((back.b() - input.b()).abs() < tolerance, "{:?}: {:?}", input, back);
+    }
+
+    // a grid over the whole cube, and colors near 1 that the fit from grey
+    // alone left in a local minimum
+    fn colors() -> Vec<LinearSrgb> {
+        let steps = [0.0, 0.05, 0.2, 0.4, 0.6, 0.8, 0.9, 0.95, 0.99, 1.0];
+        let mut colors = vec![LinearSrgb::new(0.8, 1.0, 0.4), LinearSrgb::new(0.8, 0.99, 0.6),
+                              LinearSrgb::new(0.95, 1.0, 0.9), LinearSrgb::new(0.727, 1.0, 0.545)];
+        for &r in &steps {
+            for &g in &steps {
+                for &b in &steps {
+                    colors.push(LinearSrgb::new(r, g, b));
+                }
+            }
+        }
+        colors
+    }
+
+    #[test]
+    fn test_fit() {
+        let d65: Spd = From::from(Daylight::new(ColorTemp::new(6504.0)).unwrap());
+        for input in colors() {
+            let xyz: Cie1931<D65> = From::from(input);
+            round_trip(input, SigmoidSpectrum::fit(&xyz, &d65), 0.001);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // interpolated from the table, so less exact than a fit
+        for input in colors() {
+            round_trip(input, From::from(input), 0.01);
+        }
+    }
+
+    #[test]
+    fn test_plausible() {
+        // a red surface reflects long wavelengths, and stays within 0..1
+        let red: SigmoidSpectrum = From::from(LinearSrgb::new(0.8, 0.05, 0.05));
+        assert!(red.value_at(650.0) > 0.7);
+        assert!(red.value_at(450.0) < 0.1);
+        for w in (380..=780).step_by(10) {
+            let v = red.value_at(w as f32);
+            assert!((0.0..=1.0).contains(&v));
+        }
+
+        let grey: SigmoidSpectrum = From::from(LinearSrgb::new(0.18, 0.18, 0.18));
+        assert!((grey.value_at(400.0) - grey.value_at(700.0)).abs() < 0.001);
+    }
+}